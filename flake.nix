@@ -42,6 +42,7 @@
               )
               unfilteredRoot)
             (pkgs.lib.fileset.maybeMissing ./assets)
+            (pkgs.lib.fileset.maybeMissing ./tests/fixtures)
           ];
         };
 
@@ -112,10 +113,11 @@
             cat > $out/share/applications/ndownloader.desktop << EOF
             [Desktop Entry]
             Name=ndownloader
-            Exec=$out/bin/ndownloader
+            Exec=$out/bin/ndownloader %u
             Icon=ndownloader
             Type=Application
             Categories=Network;AudioVideo;
+            MimeType=x-scheme-handler/ndownloader;
             EOF
           '';
         };
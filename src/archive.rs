@@ -0,0 +1,207 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Vidéo déjà téléchargée, identifiée par sa plateforme et son ID
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveEntry {
+    /// Clé d'extracteur yt-dlp en minuscules (ex: "youtube", "twitchvod")
+    pub platform: String,
+    pub video_id: String,
+    /// Inconnu pour les entrées importées d'une archive yt-dlp
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+    #[serde(default)]
+    pub size: Option<u64>,
+    /// SHA-256 du fichier
+    #[serde(default)]
+    pub checksum: Option<String>,
+    pub downloaded_at: DateTime<Utc>,
+}
+
+/// Archive persistante des vidéos téléchargées
+pub struct DownloadArchive {
+    file: PathBuf,
+    entries: RwLock<HashMap<String, ArchiveEntry>>,
+}
+
+/// Clé au format de `--download-archive` de yt-dlp: "<extracteur> <id>"
+pub fn archive_key(platform: &str, video_id: &str) -> String {
+    format!("{} {}", platform.to_lowercase(), video_id)
+}
+
+impl DownloadArchive {
+    pub fn open(file: PathBuf) -> Self {
+        let entries = match Self::load_from_disk(&file) {
+            Ok(entries) => entries,
+            Err(error) => {
+                tracing::debug!("Pas d'archive de téléchargement: {}", error);
+                HashMap::new()
+            }
+        };
+
+        Self {
+            file,
+            entries: RwLock::new(entries),
+        }
+    }
+
+    /// Cherche une vidéo dans l'archive
+    pub fn get(&self, platform: &str, video_id: &str) -> Option<ArchiveEntry> {
+        self.entries
+            .read()
+            .get(&archive_key(platform, video_id))
+            .cloned()
+    }
+
+    /// Enregistre un fichier téléchargé (taille et checksum calculés ici)
+    pub fn record_file(&self, platform: &str, video_id: &str, path: &Path) -> Result<()> {
+        let size = std::fs::metadata(path)?.len();
+        let checksum = Self::checksum(path)?;
+
+        self.insert(ArchiveEntry {
+            platform: platform.to_lowercase(),
+            video_id: video_id.to_string(),
+            path: Some(path.to_path_buf()),
+            size: Some(size),
+            checksum: Some(checksum),
+            downloaded_at: Utc::now(),
+        })
+    }
+
+    /// Importe un fichier texte `--download-archive` de yt-dlp.
+    /// Retourne le nombre de nouvelles entrées.
+    pub fn import_ytdlp_archive(&self, path: &Path) -> Result<usize> {
+        let content = std::fs::read_to_string(path)?;
+        let imported = {
+            let mut entries = self.entries.write();
+            let mut imported = 0;
+            for (platform, video_id) in Self::parse_ytdlp_archive(&content) {
+                let key = archive_key(platform, video_id);
+                if entries.contains_key(&key) {
+                    continue;
+                }
+                entries.insert(
+                    key,
+                    ArchiveEntry {
+                        platform: platform.to_lowercase(),
+                        video_id: video_id.to_string(),
+                        path: None,
+                        size: None,
+                        checksum: None,
+                        downloaded_at: Utc::now(),
+                    },
+                );
+                imported += 1;
+            }
+            imported
+        };
+
+        self.save_to_disk()?;
+        Ok(imported)
+    }
+
+    fn parse_ytdlp_archive(content: &str) -> impl Iterator<Item = (&str, &str)> {
+        content.lines().filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let platform = parts.next()?;
+            let video_id = parts.next()?;
+            Some((platform, video_id))
+        })
+    }
+
+    fn insert(&self, entry: ArchiveEntry) -> Result<()> {
+        self.entries
+            .write()
+            .insert(archive_key(&entry.platform, &entry.video_id), entry);
+        self.save_to_disk()
+    }
+
+    fn checksum(path: &Path) -> Result<String> {
+        let mut file = std::fs::File::open(path)?;
+        let mut hasher = Sha256::new();
+        let mut buffer = vec![0u8; 1024 * 1024];
+        loop {
+            let read = file.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+        }
+        Ok(format!("{:x}", hasher.finalize()))
+    }
+
+    fn load_from_disk(path: &Path) -> Result<HashMap<String, ArchiveEntry>> {
+        let content = std::fs::read_to_string(path)?;
+        let entries: Vec<ArchiveEntry> = serde_json::from_str(&content)?;
+        Ok(entries
+            .into_iter()
+            .map(|entry| (archive_key(&entry.platform, &entry.video_id), entry))
+            .collect())
+    }
+
+    fn save_to_disk(&self) -> Result<()> {
+        let mut entries: Vec<ArchiveEntry> = self.entries.read().values().cloned().collect();
+        entries.sort_by_key(|entry| entry.downloaded_at);
+
+        if let Some(parent) = self.file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(&entries)?;
+        let tmp_path = self.file.with_extension("json.tmp");
+        std::fs::write(&tmp_path, content)?;
+        std::fs::rename(&tmp_path, &self.file)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let video = dir.path().join("video.mp4");
+        std::fs::write(&video, b"contenu").unwrap();
+        let archive_file = dir.path().join("archive.json");
+
+        let archive = DownloadArchive::open(archive_file.clone());
+        archive.record_file("YouTube", "abc123", &video).unwrap();
+
+        let reloaded = DownloadArchive::open(archive_file);
+        let entry = reloaded.get("youtube", "abc123").unwrap();
+        assert_eq!(entry.path, Some(video));
+        assert_eq!(entry.size, Some(7));
+        assert_eq!(entry.checksum.unwrap().len(), 64);
+        assert!(reloaded.get("youtube", "autre").is_none());
+    }
+
+    #[test]
+    fn test_import_ytdlp_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let ytdlp_archive = dir.path().join("archive.txt");
+        std::fs::write(
+            &ytdlp_archive,
+            "youtube dQw4w9WgXcQ\ntwitchvod v123456789\n\nyoutube dQw4w9WgXcQ\n",
+        )
+        .unwrap();
+
+        let archive = DownloadArchive::open(dir.path().join("archive.json"));
+        assert_eq!(archive.import_ytdlp_archive(&ytdlp_archive).unwrap(), 2);
+        assert!(archive.get("TwitchVod", "v123456789").is_some());
+        assert!(archive
+            .get("youtube", "dQw4w9WgXcQ")
+            .unwrap()
+            .path
+            .is_none());
+
+        // Une seconde importation n'ajoute rien
+        assert_eq!(archive.import_ytdlp_archive(&ytdlp_archive).unwrap(), 0);
+    }
+}
@@ -79,6 +79,9 @@ impl<T: Clone + Serialize + for<'de> Deserialize<'de>> Cache<T> {
             .map(|(k, v)| (k.clone(), v.value.clone()))
             .collect();
         let content = serde_json::to_string_pretty(&data)?;
+        if let Some(parent) = self.cache_file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
         std::fs::write(&self.cache_file, content)?;
         Ok(())
     }
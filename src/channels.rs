@@ -0,0 +1,614 @@
+use crate::config::Config;
+use crate::downloader_queue::FormatProfile;
+use crate::extractors::{
+    Dailymotion, Extractor, Generic, Kick, Odysee, PeerTube, Twitch, Vimeo, YouTube,
+};
+use crate::media_url::{MediaUrl, UrlError};
+use crate::rules::ChannelRules;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Channel {
+    /// Nom du dossier de la chaîne sur les disques
+    pub name: String,
+    pub platform: Platform,
+    pub url: String,
+    /// Identifiant stable de la chaîne (`UC...` sur YouTube, login sur Twitch)
+    #[serde(default)]
+    pub channel_id: Option<String>,
+    /// Nom affiché à la place du nom du dossier
+    #[serde(default)]
+    pub display_name: Option<String>,
+    /// Emplacement de stockage préféré, avant la stratégie globale
+    #[serde(default)]
+    pub storage_root: Option<PathBuf>,
+    /// Modèle de nom de fichier (voir `monitor::output_file_stem`)
+    #[serde(default)]
+    pub filename_template: Option<String>,
+    #[serde(default)]
+    pub format: FormatProfile,
+    #[serde(default)]
+    pub monitor: MonitorSettings,
+    #[serde(default)]
+    pub rules: ChannelRules,
+    /// Groupe dans lequel la chaîne est rangée dans la liste
+    #[serde(default)]
+    pub group: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub last_scan: Option<DateTime<Utc>>,
+    /// Position du dernier scan, pour les scans incrémentaux
+    #[serde(default)]
+    pub cursor: ScanCursor,
+    /// Parcourt tout l'historique de la chaîne au premier scan
+    #[serde(default)]
+    pub backfill: bool,
+}
+
+impl Channel {
+    /// Crée une chaîne à partir de son URL sur une plateforme reconnue
+    pub fn from_url(url: &str) -> Option<Self> {
+        Self::parse(url).ok()
+    }
+
+    /// Comme `from_url`, avec la raison du refus. L'URL est enregistrée sous
+    /// sa forme canonique, sans paramètres ni onglet.
+    pub fn parse(url: &str) -> Result<Self, UrlError> {
+        Self::from_media_url(MediaUrl::parse(url)?)
+    }
+
+    /// Comme `parse`, mais la chaîne d'un site inconnu est gardée telle quelle
+    /// et confiée à yt-dlp (plateforme "autre")
+    pub fn parse_or_generic(url: &str) -> Result<Self, UrlError> {
+        Self::from_media_url(MediaUrl::parse_or_generic(url)?)
+    }
+
+    fn from_media_url(parsed: MediaUrl) -> Result<Self, UrlError> {
+        parsed.require_channel()?;
+        Ok(Self {
+            name: parsed.channel_name().unwrap_or_default().to_string(),
+            channel_id: parsed.channel_id,
+            platform: parsed.platform,
+            url: parsed.channel_url.unwrap_or_default(),
+            display_name: None,
+            storage_root: None,
+            filename_template: None,
+            format: FormatProfile::default(),
+            monitor: MonitorSettings::default(),
+            rules: ChannelRules::default(),
+            group: None,
+            tags: Vec::new(),
+            last_scan: None,
+            cursor: ScanCursor::default(),
+            backfill: false,
+        })
+    }
+
+    /// Même chaîne, même si l'URL diffère (http, www, barre finale...)
+    pub fn is_same(&self, other: &Channel) -> bool {
+        if let (Some(id), Some(other_id)) = (&self.channel_id, &other.channel_id) {
+            return id == other_id;
+        }
+        self.platform == other.platform && self.name.eq_ignore_ascii_case(&other.name)
+    }
+
+    pub fn display_name(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Chemin du fichier téléchargé sous l'emplacement `root`
+    pub fn output_path(&self, root: &Path, file_stem: &str) -> PathBuf {
+        root.join(&self.name)
+            .join(format!("{file_stem}.{}", self.format.extension()))
+    }
+}
+
+/// Position du dernier scan d'une chaîne
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanCursor {
+    /// Vidéo la plus récente vue au dernier scan
+    pub latest_id: Option<String>,
+    /// Tout l'historique a déjà été parcouru
+    pub backfilled: bool,
+}
+
+/// Action effectuée quand la surveillance trouve de nouvelles vidéos
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MonitorMode {
+    /// Ajoute les nouvelles vidéos à la queue de téléchargement
+    AutoDownload,
+    /// Envoie seulement une notification
+    NotifyOnly,
+}
+
+/// Surveillance automatique d'une chaîne
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MonitorSettings {
+    pub enabled: bool,
+    /// Intervalle entre deux vérifications, en minutes
+    pub interval_minutes: u64,
+    pub mode: MonitorMode,
+    /// Surveillance suspendue avec le groupe de la chaîne, `enabled` restant
+    /// le réglage propre à la chaîne
+    pub paused: bool,
+}
+
+impl Default for MonitorSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: 60,
+            mode: MonitorMode::NotifyOnly,
+            paused: false,
+        }
+    }
+}
+
+impl MonitorSettings {
+    /// La chaîne est vérifiée par la surveillance
+    pub fn is_active(&self) -> bool {
+        self.enabled && !self.paused
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_minutes.max(1) * 60)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Platform {
+    YouTube,
+    Twitch,
+    Kick,
+    Vimeo,
+    PeerTube,
+    Dailymotion,
+    Odysee,
+    /// Autre site dont yt-dlp sait lister les vidéos
+    Other,
+}
+
+impl Platform {
+    pub const ALL: [Platform; 8] = [
+        Platform::YouTube,
+        Platform::Twitch,
+        Platform::Kick,
+        Platform::Vimeo,
+        Platform::PeerTube,
+        Platform::Dailymotion,
+        Platform::Odysee,
+        Platform::Other,
+    ];
+
+    /// Prise en charge de la plateforme (liens, liste des vidéos, téléchargement)
+    pub fn extractor(&self) -> &'static dyn Extractor {
+        match self {
+            Platform::YouTube => &YouTube,
+            Platform::Twitch => &Twitch,
+            Platform::Kick => &Kick,
+            Platform::Vimeo => &Vimeo,
+            Platform::PeerTube => &PeerTube,
+            Platform::Dailymotion => &Dailymotion,
+            Platform::Odysee => &Odysee,
+            Platform::Other => &Generic,
+        }
+    }
+}
+
+/// Cherche une chaîne par URL, par nom de dossier ou par nom affiché
+/// (sans tenir compte de la casse)
+pub fn find_channel<'a>(channels: &'a [Channel], query: &str) -> Option<&'a Channel> {
+    channels
+        .iter()
+        .find(|channel| channel.url == query)
+        .or_else(|| {
+            channels
+                .iter()
+                .find(|channel| channel.name.eq_ignore_ascii_case(query))
+        })
+        .or_else(|| {
+            channels
+                .iter()
+                .find(|channel| channel.display_name().eq_ignore_ascii_case(query))
+        })
+        .or_else(|| {
+            let wanted = Channel::from_url(query)?;
+            channels.iter().find(|channel| channel.is_same(&wanted))
+        })
+}
+
+/// Chaîne suivie correspondant au nom ou à l'URL, ou chaîne non suivie pour une URL
+pub fn resolve_channel(channels: &[Channel], query: &str) -> Result<Channel> {
+    match find_channel(channels, query) {
+        Some(channel) => Ok(channel.clone()),
+        None => Channel::from_url(query).with_context(|| format!("Chaîne introuvable: {query}")),
+    }
+}
+
+/// Chaînes d'un groupe, par index dans la liste complète
+#[derive(Debug, PartialEq)]
+pub struct ChannelGroup {
+    /// `None` pour les chaînes sans groupe
+    pub name: Option<String>,
+    pub channels: Vec<usize>,
+}
+
+/// Range les chaînes par groupe (ordre alphabétique, chaînes sans groupe à la fin),
+/// en ne gardant que celles qui portent le tag `tag` s'il est donné
+pub fn group_channels(channels: &[Channel], tag: Option<&str>) -> Vec<ChannelGroup> {
+    let mut groups: Vec<ChannelGroup> = Vec::new();
+    for (index, channel) in channels.iter().enumerate() {
+        if tag.is_some_and(|tag| !channel.tags.iter().any(|t| t == tag)) {
+            continue;
+        }
+        match groups.iter_mut().find(|group| group.name == channel.group) {
+            Some(group) => group.channels.push(index),
+            None => groups.push(ChannelGroup {
+                name: channel.group.clone(),
+                channels: vec![index],
+            }),
+        }
+    }
+
+    groups.sort_by_key(|group| {
+        (
+            group.name.is_none(),
+            group.name.as_ref().map(|name| name.to_lowercase()),
+        )
+    });
+    groups
+}
+
+/// Suspend la surveillance des chaînes `indices` si l'une d'elles est active,
+/// sinon la reprend. Le réglage `enabled` de chaque chaîne est conservé.
+pub fn toggle_monitoring_pause(channels: &mut [Channel], indices: &[usize]) {
+    let pause = indices
+        .iter()
+        .any(|&index| channels[index].monitor.is_active());
+    for &index in indices {
+        channels[index].monitor.paused = pause;
+    }
+}
+
+/// Tags utilisés par au moins une chaîne, triés et sans doublon
+pub fn all_tags(channels: &[Channel]) -> Vec<String> {
+    let mut tags: Vec<String> = channels
+        .iter()
+        .flat_map(|channel| channel.tags.iter().cloned())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// Ancien emplacement de la liste des chaînes, perdu à chaque redémarrage
+const LEGACY_CHANNELS_FILE: &str = "/tmp/ndownloader_channels.json";
+
+/// Version actuelle du format du fichier des chaînes
+const CHANNELS_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct ChannelsFile {
+    version: u32,
+    channels: Vec<Channel>,
+}
+
+/// Fichier versionné, ou simple liste des versions antérieures
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredChannels {
+    Versioned(ChannelsFile),
+    Legacy(Vec<Channel>),
+}
+
+/// Lit une liste de chaînes au format JSON (fichier versionné ou ancienne liste)
+pub fn parse_channels(content: &str) -> Result<Vec<Channel>> {
+    match serde_json::from_str(content).context("Format invalide")? {
+        StoredChannels::Legacy(channels) => Ok(channels),
+        StoredChannels::Versioned(file) if file.version > CHANNELS_SCHEMA_VERSION => {
+            anyhow::bail!(
+                "Écrit par une version plus récente (format {})",
+                file.version
+            )
+        }
+        StoredChannels::Versioned(file) => Ok(file.channels),
+    }
+}
+
+/// Liste de chaînes au format JSON versionné, réglages compris
+pub fn channels_to_json(channels: &[Channel]) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&ChannelsFile {
+        version: CHANNELS_SCHEMA_VERSION,
+        channels: channels.to_vec(),
+    })?)
+}
+
+/// Liste des chaînes suivies, enregistrée dans le dossier de données
+#[derive(Debug, Clone)]
+pub struct ChannelStore {
+    file: PathBuf,
+    /// Fichier à migrer s'il n'existe pas encore de liste
+    legacy_file: Option<PathBuf>,
+}
+
+impl ChannelStore {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            file: config.data_dir.join("channels.json"),
+            legacy_file: Some(PathBuf::from(LEGACY_CHANNELS_FILE)),
+        }
+    }
+
+    fn backup_file(&self) -> PathBuf {
+        self.file.with_extension("json.bak")
+    }
+
+    /// Charge les chaînes. Un fichier illisible est remplacé par sa sauvegarde,
+    /// et l'ancien fichier de /tmp est migré au premier lancement.
+    pub fn load(&self) -> Vec<Channel> {
+        match Self::read(&self.file) {
+            Ok(Some(channels)) => return channels,
+            Ok(None) => {}
+            Err(error) => {
+                tracing::error!("Liste des chaînes illisible: {:#}", error);
+                match Self::read(&self.backup_file()) {
+                    Ok(Some(channels)) => {
+                        tracing::warn!("Chaînes restaurées depuis la sauvegarde");
+                        return channels;
+                    }
+                    Ok(None) => {}
+                    Err(error) => tracing::error!("Sauvegarde illisible: {:#}", error),
+                }
+                return Vec::new();
+            }
+        }
+
+        self.migrate_legacy().unwrap_or_default()
+    }
+
+    /// Enregistre les chaînes (écriture atomique), en conservant la version
+    /// précédente du fichier comme sauvegarde
+    pub fn save(&self, channels: &[Channel]) -> Result<()> {
+        if let Some(parent) = self.file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // Ne jamais écraser une bonne sauvegarde par un fichier corrompu
+        if matches!(Self::read(&self.file), Ok(Some(_))) {
+            std::fs::copy(&self.file, self.backup_file())?;
+        }
+
+        let content = channels_to_json(channels)?;
+        let tmp_path = self.file.with_extension("json.tmp");
+        std::fs::write(&tmp_path, content)?;
+        std::fs::rename(&tmp_path, &self.file)?;
+        Ok(())
+    }
+
+    /// Note la date et le curseur du dernier scan d'une chaîne suivie
+    pub fn record_scan(&self, url: &str, cursor: &ScanCursor) -> Result<()> {
+        self.update_channel(url, |channel| {
+            channel.last_scan = Some(Utc::now());
+            channel.cursor = cursor.clone();
+        })
+    }
+
+    /// Note la date du dernier scan d'une chaîne suivie sans toucher à son
+    /// curseur, pour les scans faits hors de la surveillance
+    pub fn record_scan_date(&self, url: &str) -> Result<()> {
+        self.update_channel(url, |channel| channel.last_scan = Some(Utc::now()))
+    }
+
+    /// Relit les chaînes et modifie la seule chaîne `url`
+    fn update_channel(&self, url: &str, f: impl FnOnce(&mut Channel)) -> Result<()> {
+        let mut channels = self.load();
+        let Some(channel) = channels.iter_mut().find(|channel| channel.url == url) else {
+            return Ok(());
+        };
+        f(channel);
+        self.save(&channels)
+    }
+
+    /// Lit un fichier de chaînes, `None` s'il n'existe pas
+    fn read(path: &Path) -> Result<Option<Vec<Channel>>> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error.into()),
+        };
+
+        parse_channels(&content)
+            .with_context(|| format!("Lecture de {} impossible", path.display()))
+            .map(Some)
+    }
+
+    fn migrate_legacy(&self) -> Option<Vec<Channel>> {
+        let legacy_file = self.legacy_file.as_ref()?;
+        let channels = match Self::read(legacy_file) {
+            Ok(channels) => channels?,
+            Err(error) => {
+                tracing::warn!("Ancienne liste des chaînes illisible: {:#}", error);
+                return None;
+            }
+        };
+
+        match self.save(&channels) {
+            Ok(()) => tracing::info!(
+                "{} chaîne(s) migrée(s) de {} vers {}",
+                channels.len(),
+                legacy_file.display(),
+                self.file.display()
+            ),
+            Err(error) => tracing::error!("Migration des chaînes impossible: {:#}", error),
+        }
+        Some(channels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_old_channel_entries_load_with_default_monitoring() {
+        let json = r#"[{"name":"chaine","platform":"Twitch","url":"https://twitch.tv/chaine"}]"#;
+        let channels: Vec<Channel> = serde_json::from_str(json).unwrap();
+        assert_eq!(channels[0].monitor, MonitorSettings::default());
+        assert!(!channels[0].monitor.enabled);
+        assert!(channels[0].rules.is_empty());
+        assert_eq!(channels[0].display_name(), "chaine");
+        assert_eq!(channels[0].format, FormatProfile::Best);
+        assert!(channels[0].tags.is_empty());
+        assert!(channels[0].last_scan.is_none());
+    }
+
+    #[test]
+    fn test_output_path_uses_folder_name_and_format() {
+        let mut channel = Channel::from_url("https://twitch.tv/chaine").unwrap();
+        channel.display_name = Some("La Chaîne".to_string());
+        channel.format = FormatProfile::AudioOnly;
+        assert_eq!(
+            channel.output_path(Path::new("/videos"), "titre [id]"),
+            PathBuf::from("/videos/chaine/titre [id].m4a")
+        );
+    }
+
+    fn store(dir: &Path) -> ChannelStore {
+        ChannelStore {
+            file: dir.join("data/channels.json"),
+            legacy_file: Some(dir.join("legacy.json")),
+        }
+    }
+
+    #[test]
+    fn test_store_migrates_legacy_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path());
+        std::fs::write(
+            dir.path().join("legacy.json"),
+            r#"[{"name":"chaine","platform":"Twitch","url":"https://twitch.tv/chaine"}]"#,
+        )
+        .unwrap();
+
+        let channels = store.load();
+        assert_eq!(channels.len(), 1);
+
+        let content = std::fs::read_to_string(&store.file).unwrap();
+        let file: ChannelsFile = serde_json::from_str(&content).unwrap();
+        assert_eq!(file.version, CHANNELS_SCHEMA_VERSION);
+        assert_eq!(file.channels[0].name, "chaine");
+    }
+
+    #[test]
+    fn test_store_keeps_backup_and_recovers_from_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path());
+        let first = Channel::from_url("https://twitch.tv/premiere").unwrap();
+        let second = Channel::from_url("https://twitch.tv/seconde").unwrap();
+
+        store.save(std::slice::from_ref(&first)).unwrap();
+        store
+            .record_scan(&first.url, &ScanCursor::default())
+            .unwrap();
+        assert!(store.load()[0].last_scan.is_some());
+        store.save(&[first, second]).unwrap();
+        assert_eq!(store.load().len(), 2);
+
+        // Fichier corrompu: la version précédente est restaurée
+        std::fs::write(&store.file, "{").unwrap();
+        let channels = store.load();
+        assert_eq!(channels.len(), 1);
+        assert_eq!(channels[0].name, "premiere");
+    }
+
+    #[test]
+    fn test_store_refuses_newer_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("channels.json");
+        std::fs::write(&path, r#"{"version": 99, "channels": []}"#).unwrap();
+        assert!(ChannelStore::read(&path).is_err());
+    }
+
+    #[test]
+    fn test_find_channel_by_url_or_name() {
+        let channels: Vec<Channel> = ["https://www.youtube.com/@Chaine", "https://twitch.tv/autre"]
+            .into_iter()
+            .filter_map(Channel::from_url)
+            .collect();
+
+        assert_eq!(
+            find_channel(&channels, "https://twitch.tv/autre").map(|c| c.name.as_str()),
+            Some("autre")
+        );
+        assert_eq!(
+            find_channel(&channels, "chaine").map(|c| c.platform.clone()),
+            Some(Platform::YouTube)
+        );
+        assert!(find_channel(&channels, "inconnue").is_none());
+    }
+
+    #[test]
+    fn test_group_pause_keeps_monitoring_settings() {
+        let mut channels: Vec<Channel> = ["https://twitch.tv/a", "https://twitch.tv/b"]
+            .into_iter()
+            .filter_map(Channel::from_url)
+            .collect();
+        channels[0].monitor.enabled = true;
+        channels[0].monitor.mode = MonitorMode::AutoDownload;
+        let original: Vec<MonitorSettings> = channels.iter().map(|c| c.monitor.clone()).collect();
+
+        toggle_monitoring_pause(&mut channels, &[0, 1]);
+        assert!(channels.iter().all(|c| !c.monitor.is_active()));
+        assert!(channels[0].monitor.enabled);
+
+        toggle_monitoring_pause(&mut channels, &[0, 1]);
+        let restored: Vec<MonitorSettings> = channels.iter().map(|c| c.monitor.clone()).collect();
+        assert_eq!(restored, original);
+    }
+
+    #[test]
+    fn test_group_channels_and_tag_filter() {
+        let mut channels: Vec<Channel> = [
+            "https://twitch.tv/a",
+            "https://twitch.tv/b",
+            "https://twitch.tv/c",
+            "https://twitch.tv/d",
+        ]
+        .into_iter()
+        .filter_map(Channel::from_url)
+        .collect();
+        channels[0].group = Some("Jeux".to_string());
+        channels[1].tags = vec!["fr".to_string()];
+        channels[2].group = Some("actu".to_string());
+        channels[2].tags = vec!["fr".to_string(), "en".to_string()];
+        channels[3].group = Some("Jeux".to_string());
+
+        let groups = group_channels(&channels, None);
+        let names: Vec<Option<&str>> = groups.iter().map(|g| g.name.as_deref()).collect();
+        assert_eq!(names, [Some("actu"), Some("Jeux"), None]);
+        assert_eq!(groups[1].channels, [0, 3]);
+
+        let groups = group_channels(&channels, Some("fr"));
+        assert_eq!(
+            groups,
+            [
+                ChannelGroup {
+                    name: Some("actu".to_string()),
+                    channels: vec![2],
+                },
+                ChannelGroup {
+                    name: None,
+                    channels: vec![1],
+                },
+            ]
+        );
+        assert_eq!(all_tags(&channels), ["en", "fr"]);
+    }
+}
@@ -0,0 +1,675 @@
+use crate::archive::DownloadArchive;
+use crate::channels::{find_channel, resolve_channel, Channel, ChannelStore};
+use crate::config::Config;
+use crate::control;
+use crate::download_progress;
+use crate::downloader_queue::{
+    self, DownloadEvent, DownloadEventKind, DownloadQueue, DownloadStatus, DownloadTask,
+    QueueConfig,
+};
+use crate::instance::InstanceLock;
+use crate::monitor::output_file_stem;
+use crate::scanner::{ScanMode, VideoScanner, PAGE_SIZE};
+use crate::subscriptions::{self, ExportFormat, ImportFormat};
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use futures::channel::mpsc;
+use futures::StreamExt;
+use gpui::{App, Application};
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Téléchargeur automatique de vidéos Twitch et YouTube
+#[derive(Debug, Parser)]
+#[command(
+    name = "ndownloader",
+    version,
+    about,
+    args_conflicts_with_subcommands = true
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Lien de chaîne ou de vidéo à ouvrir, transmis à l'instance en cours s'il y en a une
+    pub url: Option<String>,
+
+    /// Surveille les chaînes et télécharge sans interface graphique
+    #[arg(long)]
+    pub headless: bool,
+
+    /// Fichier journal du mode sans interface
+    /// (par défaut: $XDG_STATE_HOME/ndownloader/ndownloader.log)
+    #[arg(long, value_name = "FICHIER", requires = "headless")]
+    pub log_file: Option<PathBuf>,
+
+    /// Sortie au format JSON
+    #[arg(long, global = true)]
+    pub json: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Gère les chaînes suivies
+    #[command(subcommand)]
+    Channel(ChannelCommand),
+    /// Liste les vidéos récentes d'une chaîne
+    Scan {
+        /// Nom ou URL de la chaîne
+        channel: String,
+        /// Parcourt tout l'historique de la chaîne
+        #[arg(long, conflicts_with_all = ["new", "start"])]
+        all: bool,
+        /// Seulement les vidéos publiées depuis la dernière vérification de la surveillance
+        #[arg(long, conflicts_with = "start")]
+        new: bool,
+        /// Position de la première vidéo listée (0 = la plus récente)
+        #[arg(long, default_value_t = 0)]
+        start: usize,
+        /// Charge les métadonnées complètes (date, taille...) de chaque vidéo
+        #[arg(long)]
+        details: bool,
+    },
+    /// Télécharge une vidéo
+    Download {
+        url: String,
+        /// Nom du fichier, sans extension (par défaut: titre et ID de la vidéo)
+        #[arg(long)]
+        name: Option<String>,
+        /// Dossier de chaîne de destination (par défaut: auteur de la vidéo)
+        #[arg(long)]
+        channel: Option<String>,
+    },
+    /// Consulte et modifie la queue de téléchargement sauvegardée
+    #[command(subcommand)]
+    Queue(QueueCommand),
+    /// Consulte l'archive des vidéos téléchargées
+    #[command(subcommand)]
+    Archive(ArchiveCommand),
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ChannelCommand {
+    /// Ajoute une chaîne (YouTube, Twitch, Kick, Vimeo, PeerTube, Dailymotion,
+    /// Odysee ou tout site que yt-dlp sait lister)
+    Add {
+        url: String,
+        /// Parcourt tout l'historique de la chaîne au premier scan
+        #[arg(long)]
+        backfill: bool,
+    },
+    /// Liste les chaînes
+    List,
+    /// Supprime une chaîne
+    Remove {
+        /// Nom ou URL de la chaîne
+        channel: String,
+    },
+    /// Importe des abonnements (OPML, CSV Google Takeout, chaînes suivies Twitch,
+    /// export JSON ou liste d'URL)
+    Import {
+        file: PathBuf,
+        /// Affiche seulement ce qui serait importé
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Exporte les chaînes en JSON (réglages compris) ou en OPML selon l'extension
+    Export {
+        /// Fichier de destination, "-" pour la sortie standard (JSON)
+        file: PathBuf,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum QueueCommand {
+    /// Liste les tâches de la queue
+    List,
+    /// Annule une tâche en attente ou en pause
+    Cancel { video_id: String },
+    /// Remet en attente une tâche échouée ou annulée
+    Retry { video_id: String },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ArchiveCommand {
+    /// Indique si une vidéo a déjà été téléchargée (code de sortie 1 sinon)
+    Check { url: String },
+}
+
+/// Exécute une sous-commande
+pub fn run(command: Command, config: Config, json: bool) -> Result<()> {
+    match command {
+        Command::Channel(command) => run_channel(command, &config, json),
+        Command::Scan {
+            channel,
+            all,
+            new,
+            start,
+            details,
+        } => {
+            let mode = if all {
+                ScanMode::Backfill
+            } else if new {
+                ScanMode::Incremental
+            } else {
+                ScanMode::Page {
+                    start,
+                    count: PAGE_SIZE,
+                }
+            };
+            scan(&config, &channel, mode, details, json)
+        }
+        Command::Download { url, name, channel } => download(config, url, name, channel, json),
+        Command::Queue(command) => run_queue(command, &config, json),
+        Command::Archive(ArchiveCommand::Check { url }) => check_archive(&config, &url, json),
+    }
+}
+
+fn print_json(value: &impl Serialize) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+fn open_archive(config: &Config) -> Arc<DownloadArchive> {
+    Arc::new(DownloadArchive::open(config.data_dir.join("archive.json")))
+}
+
+/// Verrou de l'instance, tenu pendant une modification hors ligne des chaînes
+/// ou de la queue. Refusé si une instance tourne sans répondre sur son socket.
+fn lock_offline() -> Result<InstanceLock> {
+    InstanceLock::acquire()?
+        .context("NDownloader est lancé mais ne répond pas: modification annulée")
+}
+
+fn run_channel(command: ChannelCommand, config: &Config, json: bool) -> Result<()> {
+    let store = ChannelStore::from_config(config);
+    let channels = store.load();
+
+    match command {
+        ChannelCommand::Add { url, backfill } => {
+            let params = json!({ "url": url, "backfill": backfill });
+            let channel = match control::call("channel.add", params)? {
+                Some(channel) => serde_json::from_value(channel)?,
+                None => add_channel(&store, &url, backfill)?,
+            };
+
+            if json {
+                print_json(&channel)?;
+            } else {
+                println!("Chaîne ajoutée: {} ({:?})", channel.name, channel.platform);
+            }
+        }
+        ChannelCommand::List => {
+            if json {
+                print_json(&channels)?;
+            } else {
+                for channel in &channels {
+                    let monitoring = match (channel.monitor.enabled, channel.monitor.paused) {
+                        (false, _) => "non surveillée",
+                        (true, true) => "suspendue",
+                        (true, false) => "surveillée",
+                    };
+                    println!(
+                        "{:<30} {:<8} {:<15} {}",
+                        channel.display_name(),
+                        format!("{:?}", channel.platform),
+                        monitoring,
+                        channel.url
+                    );
+                }
+            }
+        }
+        ChannelCommand::Remove { channel } => {
+            let removed = match control::call("channel.remove", json!({ "channel": channel }))? {
+                Some(removed) => serde_json::from_value(removed)?,
+                None => remove_channel(&store, &channel)?,
+            };
+
+            if json {
+                print_json(&removed)?;
+            } else {
+                println!("Chaîne supprimée: {}", removed.name);
+            }
+        }
+        ChannelCommand::Import { file, dry_run } => {
+            import_channels(&store, &channels, &file, dry_run, json)?
+        }
+        ChannelCommand::Export { file } => {
+            if file.as_os_str() == "-" {
+                print!(
+                    "{}",
+                    subscriptions::export_channels(&channels, ExportFormat::Json)?
+                );
+            } else {
+                let content =
+                    subscriptions::export_channels(&channels, ExportFormat::from_path(&file))?;
+                std::fs::write(&file, content)
+                    .with_context(|| format!("Écriture de {} impossible", file.display()))?;
+                println!(
+                    "{} chaîne(s) exportée(s) vers {}",
+                    channels.len(),
+                    file.display()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn import_channels(
+    store: &ChannelStore,
+    channels: &[Channel],
+    file: &Path,
+    dry_run: bool,
+    json: bool,
+) -> Result<()> {
+    let content = std::fs::read_to_string(file)
+        .with_context(|| format!("Lecture de {} impossible", file.display()))?;
+    let format = ImportFormat::detect(file, &content);
+    let preview = subscriptions::preview_import(&content, format, channels)?;
+
+    if json {
+        print_json(&preview)?;
+    } else {
+        println!("Format: {}", format.label());
+        for channel in &preview.channels {
+            println!("  + {:<30} {}", channel.display_name(), channel.url);
+        }
+        for channel in &preview.duplicates {
+            println!("  = {:<30} déjà suivie", channel.display_name());
+        }
+        for invalid in &preview.invalid {
+            println!("  ! {invalid}: URL non prise en charge");
+        }
+    }
+    if dry_run || preview.channels.is_empty() {
+        return Ok(());
+    }
+
+    let imported = serde_json::to_value(&preview.channels)?;
+    let added: Vec<Channel> =
+        match control::call("channel.import", json!({ "channels": imported }))? {
+            Some(added) => serde_json::from_value(added)?,
+            None => {
+                let _lock = lock_offline()?;
+                let mut channels = store.load();
+                let added = subscriptions::merge_import(&mut channels, preview.channels);
+                store.save(&channels)?;
+                added
+            }
+        };
+    if !json {
+        println!("{} chaîne(s) ajoutée(s)", added.len());
+    }
+    Ok(())
+}
+
+fn add_channel(store: &ChannelStore, url: &str, backfill: bool) -> Result<Channel> {
+    let mut channel = Channel::parse_or_generic(url)?;
+    channel.backfill = backfill;
+    smol::block_on(VideoScanner::fill_channel_id(&mut channel));
+    let _lock = lock_offline()?;
+    let mut channels = store.load();
+    if channels.iter().any(|c| c.is_same(&channel)) {
+        anyhow::bail!("La chaîne {} est déjà suivie", channel.name);
+    }
+    channels.push(channel.clone());
+    store.save(&channels)?;
+    Ok(channel)
+}
+
+fn remove_channel(store: &ChannelStore, query: &str) -> Result<Channel> {
+    let _lock = lock_offline()?;
+    let mut channels = store.load();
+    let removed = find_channel(&channels, query)
+        .cloned()
+        .with_context(|| format!("Chaîne introuvable: {query}"))?;
+    channels.retain(|c| c.url != removed.url);
+    store.save(&channels)?;
+    Ok(removed)
+}
+
+fn scan(config: &Config, query: &str, mode: ScanMode, details: bool, json: bool) -> Result<()> {
+    // Une chaîne non suivie est scannée directement depuis son URL
+    let store = ChannelStore::from_config(config);
+    let channel = resolve_channel(&store.load(), query)?;
+    let scanner = VideoScanner::new(config, open_archive(config));
+    let videos = smol::block_on(scanner.scan_channel(&channel, mode, details))?;
+    // Le curseur reste à la surveillance, qui traite les nouvelles vidéos
+    match InstanceLock::acquire() {
+        Ok(Some(_lock)) => {
+            if let Err(error) = store.record_scan_date(&channel.url) {
+                tracing::warn!("Date de scan non enregistrée: {:#}", error);
+            }
+        }
+        Ok(None) => tracing::warn!("NDownloader est lancé: date de scan non enregistrée"),
+        Err(error) => tracing::warn!("Date de scan non enregistrée: {:#}", error),
+    }
+
+    if json {
+        return print_json(&videos);
+    }
+
+    for scanned in &videos {
+        let state = if scanned.downloaded.is_some() {
+            "téléchargée"
+        } else if scanned.rejected.is_some() {
+            "ignorée"
+        } else {
+            "nouvelle"
+        };
+        println!(
+            "{:<12} {:<10} {:<8} {}",
+            state,
+            scanned.video.upload_date.as_deref().unwrap_or("-"),
+            scanned
+                .video
+                .duration
+                .map(|duration| download_progress::format_eta(duration as u64))
+                .unwrap_or_else(|| "-".to_string()),
+            scanned.video.title
+        );
+    }
+
+    Ok(())
+}
+
+fn download(
+    config: Config,
+    url: String,
+    name: Option<String>,
+    channel: Option<String>,
+    json: bool,
+) -> Result<()> {
+    let archive = open_archive(&config);
+    let scanner = VideoScanner::new(&config, archive.clone());
+    let video = smol::block_on(scanner.fetch_video(&url))?;
+
+    if let Some(entry) = archive.get(&video.platform(), &video.id) {
+        if json {
+            print_json(&entry)?;
+        } else {
+            println!("Déjà téléchargée: {}", video.title);
+        }
+        return Ok(());
+    }
+
+    let channel_name = channel
+        .or_else(|| video.uploader.clone())
+        .context("Auteur de la vidéo inconnu, précisez --channel")?;
+    let channels = ChannelStore::from_config(&config).load();
+    let channel = find_channel(&channels, &channel_name);
+    let folder = channel.map_or(channel_name.as_str(), |c| c.name.as_str());
+    let format = channel.map(|c| c.format).unwrap_or_default();
+    let storage = scanner.find_best_storage_path(
+        folder,
+        channel.and_then(|c| c.storage_root.as_deref()),
+        video.filesize_approx,
+    )?;
+    let file_stem = name.unwrap_or_else(|| {
+        output_file_stem(&video, channel.and_then(|c| c.filename_template.as_deref()))
+    });
+    let output_path = storage
+        .path
+        .join(folder)
+        .join(format!("{file_stem}.{}", format.extension()));
+
+    // Queue dédiée: la queue sauvegardée reste réservée à l'application
+    let state_file =
+        std::env::temp_dir().join(format!("ndownloader-cli-{}.json", std::process::id()));
+    let queue_config = QueueConfig {
+        max_concurrent: 1,
+        state_file: state_file.clone(),
+        ..config.queue_config()
+    };
+    let result: Arc<Mutex<Option<Result<PathBuf>>>> = Arc::new(Mutex::new(None));
+    let task_result = result.clone();
+
+    Application::headless().run(move |cx: &mut App| {
+        let queue = DownloadQueue::with_config(cx, queue_config, archive);
+        let mut events = queue.subscribe();
+
+        cx.spawn(async move |cx| {
+            let outcome = match queue
+                .add_download(
+                    video.id.clone(),
+                    video.platform(),
+                    url,
+                    video.title.clone(),
+                    output_path,
+                    format,
+                )
+                .await
+            {
+                Ok(()) => wait_for_download(&mut events, json).await,
+                Err(error) => Err(error),
+            };
+            *task_result.lock() = Some(outcome);
+            cx.update(|cx| cx.quit()).ok();
+        })
+        .detach();
+    });
+
+    std::fs::remove_file(&state_file).ok();
+    let output_path = result
+        .lock()
+        .take()
+        .unwrap_or_else(|| Err(anyhow::anyhow!("Téléchargement interrompu")))?;
+
+    if json {
+        print_json(&serde_json::json!({ "output_path": output_path }))?;
+    } else {
+        println!("Téléchargée: {}", output_path.display());
+    }
+
+    Ok(())
+}
+
+/// Suit les événements de la queue jusqu'à la fin du téléchargement
+async fn wait_for_download(
+    events: &mut mpsc::UnboundedReceiver<DownloadEvent>,
+    quiet: bool,
+) -> Result<PathBuf> {
+    while let Some(event) = events.next().await {
+        match event.kind {
+            DownloadEventKind::Progress { progress, detail } if !quiet => {
+                let speed = detail
+                    .speed
+                    .map(download_progress::format_speed)
+                    .unwrap_or_default();
+                eprint!("\r{:>3.0}% {:<12}", progress * 100.0, speed);
+                std::io::stderr().flush().ok();
+            }
+            DownloadEventKind::Retrying { reason, delay_secs } if !quiet => {
+                eprintln!("\n{reason} (nouvelle tentative dans {delay_secs}s)");
+            }
+            DownloadEventKind::Completed { output_path } => {
+                if !quiet {
+                    eprintln!();
+                }
+                return Ok(output_path);
+            }
+            DownloadEventKind::Failed { reason } => anyhow::bail!(reason),
+            DownloadEventKind::Cancelled => anyhow::bail!("Téléchargement annulé"),
+            _ => {}
+        }
+    }
+
+    anyhow::bail!("Téléchargement interrompu")
+}
+
+fn run_queue(command: QueueCommand, config: &Config, json: bool) -> Result<()> {
+    // Instance en cours: elle seule fait foi pour la queue
+    let (method, params) = match &command {
+        QueueCommand::List => ("queue.list", Value::Null),
+        QueueCommand::Cancel { video_id } => ("queue.cancel", json!({ "video_id": video_id })),
+        QueueCommand::Retry { video_id } => ("queue.retry", json!({ "video_id": video_id })),
+    };
+    if let Some(result) = control::call(method, params)? {
+        if json {
+            return print_json(&result);
+        }
+        match command {
+            QueueCommand::List => {
+                print_tasks(&serde_json::from_value::<Vec<DownloadTask>>(result)?)
+            }
+            _ => {
+                let task: DownloadTask = serde_json::from_value(result)?;
+                println!("{}: {}", task.title, status_label(&task.status));
+            }
+        }
+        return Ok(());
+    }
+
+    let state_file = config.queue_config().state_file;
+    let load = || -> Result<Vec<DownloadTask>> {
+        if state_file.exists() {
+            downloader_queue::load_tasks(&state_file)
+        } else {
+            Ok(Vec::new())
+        }
+    };
+
+    let (video_id, retry) = match command {
+        QueueCommand::List => {
+            let tasks = load()?;
+            if json {
+                return print_json(&tasks);
+            }
+            print_tasks(&tasks);
+            return Ok(());
+        }
+        QueueCommand::Cancel { video_id } => (video_id, false),
+        QueueCommand::Retry { video_id } => (video_id, true),
+    };
+
+    let _lock = lock_offline()?;
+    let mut tasks = load()?;
+
+    let task = tasks
+        .iter_mut()
+        .find(|t| t.video_id == video_id)
+        .with_context(|| format!("Tâche introuvable: {video_id}"))?;
+    let changed = if retry {
+        task.reset_for_retry()
+    } else {
+        task.cancel_pending()
+    };
+    if !changed {
+        anyhow::bail!("La tâche {video_id} est {}", status_label(&task.status));
+    }
+    let task = task.clone();
+    downloader_queue::save_tasks(&state_file, &tasks)?;
+
+    if json {
+        print_json(&task)?;
+    } else {
+        println!("{}: {}", task.title, status_label(&task.status));
+        println!("La modification sera prise en compte au prochain lancement");
+    }
+
+    Ok(())
+}
+
+fn print_tasks(tasks: &[DownloadTask]) {
+    for task in tasks {
+        println!(
+            "{:<14} {:<12} {:>4.0}% {}",
+            task.video_id,
+            status_label(&task.status),
+            task.progress * 100.0,
+            task.title
+        );
+    }
+}
+
+fn status_label(status: &DownloadStatus) -> &'static str {
+    match status {
+        DownloadStatus::Queued => "en attente",
+        DownloadStatus::Downloading => "en cours",
+        DownloadStatus::Completed => "terminée",
+        DownloadStatus::Failed(_) => "en échec",
+        DownloadStatus::Paused => "en pause",
+        DownloadStatus::Cancelled => "annulée",
+    }
+}
+
+fn check_archive(config: &Config, url: &str, json: bool) -> Result<()> {
+    let archive = open_archive(config);
+    let scanner = VideoScanner::new(config, archive.clone());
+    let video = smol::block_on(scanner.fetch_video(url))?;
+    let entry = archive.get(&video.platform(), &video.id);
+
+    if json {
+        print_json(&serde_json::json!({
+            "platform": video.platform(),
+            "video_id": video.id,
+            "title": video.title,
+            "archived": entry.is_some(),
+            "entry": entry,
+        }))?;
+    } else {
+        match &entry {
+            Some(entry) => println!(
+                "Déjà téléchargée: {} ({})",
+                video.title,
+                entry
+                    .path
+                    .as_ref()
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_else(|| "archive yt-dlp".to_string())
+            ),
+            None => println!("Pas encore téléchargée: {}", video.title),
+        }
+    }
+
+    if entry.is_none() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_subcommands() {
+        let cli = Cli::try_parse_from(["ndownloader", "queue", "retry", "abc", "--json"]).unwrap();
+        assert!(cli.json);
+        assert!(matches!(
+            cli.command,
+            Some(Command::Queue(QueueCommand::Retry { ref video_id })) if video_id == "abc"
+        ));
+
+        let cli =
+            Cli::try_parse_from(["ndownloader", "channel", "import", "subs.csv", "--dry-run"])
+                .unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Channel(ChannelCommand::Import {
+                dry_run: true,
+                ..
+            }))
+        ));
+
+        let cli = Cli::try_parse_from(["ndownloader", "scan", "chaine", "--start", "30"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Scan {
+                start: 30,
+                all: false,
+                ..
+            })
+        ));
+        assert!(Cli::try_parse_from(["ndownloader", "scan", "chaine", "--all", "--new"]).is_err());
+
+        // --log-file n'a de sens qu'avec --headless
+        assert!(Cli::try_parse_from(["ndownloader", "--log-file", "/tmp/log"]).is_err());
+    }
+}
@@ -0,0 +1,318 @@
+use crate::downloader_queue::QueueConfig;
+use crate::paths;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use thiserror::Error;
+
+/// Rôle d'un emplacement de stockage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageRole {
+    /// Destination normale des téléchargements
+    Primary,
+    /// Utilisé quand aucun emplacement principal n'est disponible
+    Overflow,
+    /// Consulté pour détecter les vidéos déjà présentes, jamais écrit
+    Archive,
+}
+
+impl StorageRole {
+    pub fn is_writable(self) -> bool {
+        matches!(self, Self::Primary | Self::Overflow)
+    }
+}
+
+/// Choix de l'emplacement d'un nouveau téléchargement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageStrategy {
+    /// L'emplacement avec le plus d'espace libre
+    MostFreeSpace,
+    /// Le premier emplacement, dans l'ordre du fichier, tant qu'il reste la réserve
+    FillFirst,
+    /// Chaque emplacement à tour de rôle
+    RoundRobin,
+    /// L'emplacement contenant déjà le dossier de la chaîne
+    SameDiskAsChannel,
+}
+
+/// Racine de stockage (un sous-dossier par chaîne)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StorageRoot {
+    pub path: PathBuf,
+    pub role: StorageRole,
+}
+
+/// Valeurs par défaut de l'application
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Defaults {
+    /// Nombre maximal de téléchargements simultanés
+    pub max_concurrent_downloads: usize,
+    /// Durée de validité du cache des scans de chaînes, en secondes
+    pub scan_cache_ttl_secs: u64,
+    pub storage_strategy: StorageStrategy,
+    /// Espace à toujours laisser libre sur chaque emplacement, en Gio
+    pub storage_reserve_gib: u64,
+}
+
+impl Default for Defaults {
+    fn default() -> Self {
+        Self {
+            max_concurrent_downloads: 3,
+            scan_cache_ttl_secs: 300,
+            storage_strategy: StorageStrategy::MostFreeSpace,
+            storage_reserve_gib: 10,
+        }
+    }
+}
+
+/// Configuration utilisateur ($XDG_CONFIG_HOME/ndownloader/config.toml)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Données persistantes (queue, archive des téléchargements)
+    pub data_dir: PathBuf,
+    /// Caches pouvant être supprimés sans perte
+    pub cache_dir: PathBuf,
+    pub defaults: Defaults,
+    pub storage: Vec<StorageRoot>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        let root = |path: &str, role| StorageRoot {
+            path: PathBuf::from(path),
+            role,
+        };
+
+        Self {
+            data_dir: paths::data_dir(),
+            cache_dir: paths::cache_dir(),
+            defaults: Defaults::default(),
+            storage: vec![
+                root("/run/mount/ve_stock_1", StorageRole::Primary),
+                root("/run/mount/ve_stock_2", StorageRole::Overflow),
+                root("/run/mount/ve_ext_1", StorageRole::Archive),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("Impossible de lire {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Configuration invalide dans {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error("Impossible d'écrire {path}: {source}")]
+    Write {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Aucun emplacement de stockage principal ou de débordement n'est défini")]
+    NoWritableStorage,
+    #[error("Le chemin {0} doit être absolu")]
+    RelativePath(PathBuf),
+    #[error("L'emplacement {0} est défini plusieurs fois")]
+    DuplicateStorage(PathBuf),
+    #[error("La valeur de {0} doit être supérieure à zéro")]
+    ZeroValue(&'static str),
+}
+
+/// Chemin du fichier de configuration
+pub fn config_file() -> PathBuf {
+    paths::config_dir().join("config.toml")
+}
+
+impl Config {
+    /// Charge et valide la configuration, ou la configuration par défaut
+    /// si le fichier n'existe pas encore
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::load_from(&config_file())
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self, ConfigError> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                tracing::info!("Pas de configuration, valeurs par défaut utilisées");
+                return Ok(Self::default());
+            }
+            Err(source) => {
+                return Err(ConfigError::Read {
+                    path: path.to_path_buf(),
+                    source,
+                })
+            }
+        };
+
+        let config: Self = toml::from_str(&content).map_err(|source| ConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Valide puis enregistre la configuration
+    pub fn save(&self) -> Result<(), ConfigError> {
+        self.save_to(&config_file())
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), ConfigError> {
+        self.validate()?;
+
+        let write_error = |source| ConfigError::Write {
+            path: path.to_path_buf(),
+            source,
+        };
+        let content = toml::to_string_pretty(self)
+            .map_err(|error| write_error(std::io::Error::other(error)))?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(write_error)?;
+        }
+        let tmp_path = path.with_extension("toml.tmp");
+        std::fs::write(&tmp_path, content).map_err(write_error)?;
+        std::fs::rename(&tmp_path, path).map_err(write_error)?;
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        for dir in [&self.data_dir, &self.cache_dir] {
+            if !dir.is_absolute() {
+                return Err(ConfigError::RelativePath(dir.clone()));
+            }
+        }
+
+        let mut seen = HashSet::new();
+        for root in &self.storage {
+            if !root.path.is_absolute() {
+                return Err(ConfigError::RelativePath(root.path.clone()));
+            }
+            if !seen.insert(&root.path) {
+                return Err(ConfigError::DuplicateStorage(root.path.clone()));
+            }
+        }
+
+        if !self.storage.iter().any(|root| root.role.is_writable()) {
+            return Err(ConfigError::NoWritableStorage);
+        }
+        if self.defaults.max_concurrent_downloads == 0 {
+            return Err(ConfigError::ZeroValue("max_concurrent_downloads"));
+        }
+
+        Ok(())
+    }
+
+    pub fn storage_reserve_bytes(&self) -> u64 {
+        self.defaults.storage_reserve_gib * 1024 * 1024 * 1024
+    }
+
+    pub fn scan_cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.defaults.scan_cache_ttl_secs)
+    }
+
+    /// Configuration de la queue de téléchargement dérivée de ces réglages
+    pub fn queue_config(&self) -> QueueConfig {
+        QueueConfig {
+            max_concurrent: self.defaults.max_concurrent_downloads,
+            state_file: self.data_dir.join("queue.json"),
+            ..QueueConfig::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_missing_file_uses_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config::load_from(&dir.path().join("config.toml")).unwrap();
+        assert_eq!(config, Config::default());
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_parse_and_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("config.toml");
+        std::fs::write(
+            &file,
+            r#"
+data_dir = "/srv/ndownloader"
+
+[defaults]
+max_concurrent_downloads = 5
+storage_strategy = "fill_first"
+
+[[storage]]
+path = "/mnt/videos"
+role = "primary"
+
+[[storage]]
+path = "/mnt/old"
+role = "archive"
+"#,
+        )
+        .unwrap();
+
+        let config = Config::load_from(&file).unwrap();
+        assert_eq!(config.data_dir, PathBuf::from("/srv/ndownloader"));
+        assert_eq!(config.defaults.max_concurrent_downloads, 5);
+        assert_eq!(config.defaults.scan_cache_ttl_secs, 300);
+        assert_eq!(config.defaults.storage_strategy, StorageStrategy::FillFirst);
+        assert_eq!(config.storage[1].role, StorageRole::Archive);
+        assert_eq!(config.queue_config().max_concurrent, 5);
+
+        config.save_to(&file).unwrap();
+        assert_eq!(Config::load_from(&file).unwrap(), config);
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        let mut config = Config::default();
+        config.storage.push(StorageRoot {
+            path: PathBuf::from("videos"),
+            role: StorageRole::Primary,
+        });
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::RelativePath(_))
+        ));
+
+        let mut config = Config::default();
+        config.storage.push(config.storage[0].clone());
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::DuplicateStorage(_))
+        ));
+
+        let mut config = Config::default();
+        config
+            .storage
+            .retain(|root| root.role == StorageRole::Archive);
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::NoWritableStorage)
+        ));
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("config.toml");
+        std::fs::write(&file, "storage = \"pas une liste\"").unwrap();
+        assert!(matches!(
+            Config::load_from(&file),
+            Err(ConfigError::Parse { .. })
+        ));
+    }
+}
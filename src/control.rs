@@ -0,0 +1,625 @@
+use crate::channels::{find_channel, resolve_channel, Channel, ChannelStore, MonitorSettings};
+use crate::downloader_queue::{DownloadQueue, DownloadTask};
+use crate::monitor::output_file_stem;
+use crate::notifications::Notification;
+use crate::paths;
+use crate::rules::ChannelRules;
+use crate::scanner::{ScanMode, VideoScanner, PAGE_SIZE};
+use crate::subscriptions::merge_import;
+use anyhow::{Context, Result};
+use futures::channel::mpsc;
+use futures::StreamExt;
+use gpui::{App, BackgroundExecutor};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use smol::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use smol::net::unix::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// Erreur applicative (chaîne introuvable, yt-dlp en échec...)
+const SERVER_ERROR: i64 = -32000;
+
+/// Socket de contrôle de l'instance en cours ($XDG_RUNTIME_DIR/ndownloader.sock)
+pub fn socket_path() -> PathBuf {
+    match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(runtime_dir) => PathBuf::from(runtime_dir).join("ndownloader.sock"),
+        None => paths::state_dir().join("control.sock"),
+    }
+}
+
+/// Changement signalé à l'interface par l'API de contrôle ou la surveillance
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ControlNotice {
+    /// La liste des chaînes a été modifiée sur disque
+    ChannelsChanged,
+}
+
+#[derive(Debug, Deserialize)]
+struct Request {
+    /// Absent pour une notification JSON-RPC: pas de réponse
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct Response {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<anyhow::Error> for RpcError {
+    fn from(error: anyhow::Error) -> Self {
+        Self::new(SERVER_ERROR, error.to_string())
+    }
+}
+
+impl Response {
+    fn new(id: Value, result: Result<Value, RpcError>) -> Self {
+        let (result, error) = match result {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result,
+            error,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ChannelParams {
+    /// Nom ou URL de la chaîne
+    channel: String,
+}
+
+#[derive(Deserialize)]
+struct UrlParams {
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct AddChannelParams {
+    url: String,
+    #[serde(default)]
+    backfill: bool,
+}
+
+#[derive(Deserialize)]
+struct ScanParams {
+    channel: String,
+    #[serde(default)]
+    mode: ScanModeParam,
+    #[serde(default)]
+    start: usize,
+    #[serde(default)]
+    details: bool,
+}
+
+/// Étendue d'un scan demandée par `scan`
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+enum ScanModeParam {
+    #[default]
+    Page,
+    Incremental,
+    Backfill,
+}
+
+#[derive(Deserialize)]
+struct ImportParams {
+    channels: Vec<Channel>,
+}
+
+#[derive(Deserialize)]
+struct UpdateChannelParams {
+    channel: String,
+    #[serde(default)]
+    monitor: Option<MonitorSettings>,
+    #[serde(default)]
+    rules: Option<ChannelRules>,
+}
+
+#[derive(Deserialize)]
+struct EnqueueParams {
+    url: String,
+    /// Nom du fichier, sans extension
+    #[serde(default)]
+    name: Option<String>,
+    /// Dossier de chaîne de destination (par défaut: auteur de la vidéo)
+    #[serde(default)]
+    channel: Option<String>,
+}
+
+/// Résultat de la méthode `open`
+#[derive(Serialize)]
+#[serde(tag = "action", content = "item", rename_all = "snake_case")]
+enum Opened {
+    ChannelAdded(Channel),
+    Enqueued(DownloadTask),
+}
+
+#[derive(Deserialize)]
+struct TaskParams {
+    video_id: String,
+}
+
+/// API de contrôle JSON-RPC 2.0 sur socket Unix, une requête JSON par ligne.
+///
+/// Méthodes: `channel.list`, `channel.add`, `channel.import`, `channel.update`,
+/// `channel.remove`, `scan`, `queue.list`, `queue.enqueue`, `queue.cancel`, `queue.pause`,
+/// `queue.resume`, `queue.retry`, `open` et `events.subscribe`. Après `events.subscribe`,
+/// la connexion ne reçoit plus que des notifications `event` de la queue.
+/// `scan` accepte `mode` ("page", "incremental" ou "backfill"), `start` et `details`.
+#[derive(Clone)]
+pub struct ControlServer {
+    channels: ChannelStore,
+    scanner: Arc<VideoScanner>,
+    queue: Arc<DownloadQueue>,
+    notices: mpsc::UnboundedSender<ControlNotice>,
+    executor: BackgroundExecutor,
+}
+
+impl ControlServer {
+    /// Ouvre le socket de contrôle et traite les connexions en tâche de fond
+    pub fn start(
+        cx: &App,
+        channels: ChannelStore,
+        scanner: Arc<VideoScanner>,
+        queue: Arc<DownloadQueue>,
+    ) -> mpsc::UnboundedReceiver<ControlNotice> {
+        let (notices, rx) = mpsc::unbounded();
+        let server = Self {
+            channels,
+            scanner,
+            queue,
+            notices,
+            executor: cx.background_executor().clone(),
+        };
+
+        let path = socket_path();
+        let listener = match bind(&path) {
+            Ok(listener) => listener,
+            Err(error) => {
+                tracing::warn!("API de contrôle indisponible: {:#}", error);
+                return rx;
+            }
+        };
+        tracing::info!("API de contrôle sur {}", path.display());
+
+        cx.background_executor()
+            .spawn(async move {
+                loop {
+                    match listener.accept().await {
+                        Ok((stream, _)) => {
+                            let server = server.clone();
+                            server
+                                .executor
+                                .clone()
+                                .spawn(async move { server.serve(stream).await })
+                                .detach();
+                        }
+                        Err(error) => {
+                            tracing::warn!("Connexion de contrôle refusée: {}", error);
+                        }
+                    }
+                }
+            })
+            .detach();
+
+        rx
+    }
+
+    async fn serve(&self, stream: UnixStream) {
+        let mut lines = BufReader::new(stream.clone()).lines();
+        let mut writer = stream;
+
+        while let Some(Ok(line)) = lines.next().await {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let request = match serde_json::from_str::<Request>(&line) {
+                Ok(request) => request,
+                Err(error) => {
+                    let response = Response::new(
+                        Value::Null,
+                        Err(RpcError::new(PARSE_ERROR, error.to_string())),
+                    );
+                    if write_line(&mut writer, &response).await.is_err() {
+                        return;
+                    }
+                    continue;
+                }
+            };
+
+            if request.method == "events.subscribe" {
+                return self.stream_events(request.id, writer).await;
+            }
+
+            let result = self.handle(&request.method, request.params).await;
+            let Some(id) = request.id else {
+                continue;
+            };
+            if write_line(&mut writer, &Response::new(id, result))
+                .await
+                .is_err()
+            {
+                return;
+            }
+        }
+    }
+
+    /// Transmet les événements de la queue jusqu'à la fermeture de la connexion
+    async fn stream_events(&self, id: Option<Value>, mut writer: UnixStream) {
+        let mut events = self.queue.subscribe();
+        if let Some(id) = id {
+            let response = Response::new(id, Ok(Value::Bool(true)));
+            if write_line(&mut writer, &response).await.is_err() {
+                return;
+            }
+        }
+
+        while let Some(event) = events.next().await {
+            let notification = serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "event",
+                "params": event,
+            });
+            if write_line(&mut writer, &notification).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    async fn handle(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "channel.list" => to_value(self.channels.load()),
+            "channel.add" => {
+                let AddChannelParams { url, backfill } = parse_params(params)?;
+                to_value(self.add_channel(&url, backfill).await?)
+            }
+            "channel.import" => {
+                let ImportParams { channels } = parse_params(params)?;
+                to_value(self.import_channels(channels)?)
+            }
+            "channel.update" => to_value(self.update_channel(parse_params(params)?)?),
+            "channel.remove" => {
+                let ChannelParams { channel } = parse_params(params)?;
+                to_value(self.remove_channel(&channel)?)
+            }
+            "scan" => {
+                let ScanParams {
+                    channel,
+                    mode,
+                    start,
+                    details,
+                } = parse_params(params)?;
+                let mode = match mode {
+                    ScanModeParam::Page => ScanMode::Page {
+                        start,
+                        count: PAGE_SIZE,
+                    },
+                    ScanModeParam::Incremental => ScanMode::Incremental,
+                    ScanModeParam::Backfill => ScanMode::Backfill,
+                };
+                let channel = resolve_channel(&self.channels.load(), &channel)?;
+                let videos = self.scanner.scan_channel(&channel, mode, details).await?;
+                self.record_scan_date(&channel);
+                to_value(videos)
+            }
+            "queue.list" => to_value(self.queue.get_tasks()),
+            "open" => {
+                let UrlParams { url } = parse_params(params)?;
+                to_value(self.open(url).await?)
+            }
+            "queue.enqueue" => to_value(self.enqueue(parse_params(params)?).await?),
+            "queue.cancel" | "queue.pause" | "queue.resume" | "queue.retry" => {
+                let TaskParams { video_id } = parse_params(params)?;
+                match method {
+                    "queue.cancel" => self.queue.cancel(&video_id),
+                    "queue.pause" => self.queue.pause(&video_id),
+                    "queue.resume" => self.queue.resume(&video_id),
+                    _ => self.queue.retry(&video_id),
+                }
+                to_value(self.task(&video_id)?)
+            }
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Méthode inconnue: {method}"),
+            )),
+        }
+    }
+
+    async fn add_channel(&self, url: &str, backfill: bool) -> Result<Channel> {
+        let mut channel = Channel::parse_or_generic(url)?;
+        channel.backfill = backfill;
+        VideoScanner::fill_channel_id(&mut channel).await;
+        let mut channels = self.channels.load();
+        if channels.iter().any(|c| c.is_same(&channel)) {
+            anyhow::bail!("La chaîne {} est déjà suivie", channel.name);
+        }
+        channels.push(channel.clone());
+        self.save(&channels)?;
+        Ok(channel)
+    }
+
+    fn update_channel(&self, params: UpdateChannelParams) -> Result<Channel> {
+        if let Some(rules) = &params.rules {
+            rules.compile()?;
+        }
+
+        let mut channels = self.channels.load();
+        let url = find_channel(&channels, &params.channel)
+            .map(|channel| channel.url.clone())
+            .with_context(|| format!("Chaîne introuvable: {}", params.channel))?;
+        let channel = channels
+            .iter_mut()
+            .find(|channel| channel.url == url)
+            .context("Chaîne introuvable")?;
+        if let Some(monitor) = params.monitor {
+            channel.monitor = monitor;
+        }
+        if let Some(rules) = params.rules {
+            channel.rules = rules;
+        }
+
+        let channel = channel.clone();
+        self.save(&channels)?;
+        Ok(channel)
+    }
+
+    fn import_channels(&self, imported: Vec<Channel>) -> Result<Vec<Channel>> {
+        let mut channels = self.channels.load();
+        let added = merge_import(&mut channels, imported);
+        if !added.is_empty() {
+            self.save(&channels)?;
+        }
+        Ok(added)
+    }
+
+    fn remove_channel(&self, query: &str) -> Result<Channel> {
+        let mut channels = self.channels.load();
+        let removed = find_channel(&channels, query)
+            .cloned()
+            .with_context(|| format!("Chaîne introuvable: {query}"))?;
+        channels.retain(|channel| channel.url != removed.url);
+        self.save(&channels)?;
+        Ok(removed)
+    }
+
+    fn save(&self, channels: &[Channel]) -> Result<()> {
+        self.channels.save(channels)?;
+        self.notices
+            .unbounded_send(ControlNotice::ChannelsChanged)
+            .ok();
+        Ok(())
+    }
+
+    /// Le curseur reste à la surveillance, qui traite les nouvelles vidéos
+    fn record_scan_date(&self, channel: &Channel) {
+        match self.channels.record_scan_date(&channel.url) {
+            Ok(()) => {
+                self.notices
+                    .unbounded_send(ControlNotice::ChannelsChanged)
+                    .ok();
+            }
+            Err(error) => tracing::warn!("Date de scan non enregistrée: {:#}", error),
+        }
+    }
+
+    /// Ouvre un lien reçu d'une autre invocation: ajoute la chaîne,
+    /// ou met la vidéo en téléchargement
+    async fn open(&self, url: String) -> Result<Opened> {
+        let result = if Channel::from_url(&url).is_some() {
+            self.add_channel(&url, false)
+                .await
+                .map(Opened::ChannelAdded)
+        } else {
+            self.enqueue(EnqueueParams {
+                url,
+                name: None,
+                channel: None,
+            })
+            .await
+            .map(Opened::Enqueued)
+        };
+
+        match &result {
+            Ok(Opened::ChannelAdded(channel)) => {
+                Notification::success("Chaîne ajoutée", &channel.name)
+            }
+            Ok(Opened::Enqueued(task)) => Notification::info("Téléchargement ajouté", &task.title),
+            Err(error) => Notification::error("Lien non pris en charge", &error.to_string()),
+        }
+        result
+    }
+
+    async fn enqueue(&self, params: EnqueueParams) -> Result<DownloadTask> {
+        let video = self.scanner.fetch_video(&params.url).await?;
+        let channel_name = params
+            .channel
+            .or_else(|| video.uploader.clone())
+            .context("Auteur de la vidéo inconnu, précisez \"channel\"")?;
+        // Les réglages d'une chaîne suivie s'appliquent aussi aux ajouts manuels
+        let channels = self.channels.load();
+        let channel = find_channel(&channels, &channel_name);
+        let folder = channel.map_or(channel_name.as_str(), |c| c.name.as_str());
+        let format = channel.map(|c| c.format).unwrap_or_default();
+        let storage = self.scanner.find_best_storage_path(
+            folder,
+            channel.and_then(|c| c.storage_root.as_deref()),
+            video.filesize_approx,
+        )?;
+        let file_stem = params.name.unwrap_or_else(|| {
+            output_file_stem(&video, channel.and_then(|c| c.filename_template.as_deref()))
+        });
+        let output_path = storage
+            .path
+            .join(folder)
+            .join(format!("{file_stem}.{}", format.extension()));
+
+        self.queue
+            .add_download(
+                video.id.clone(),
+                video.platform(),
+                params.url,
+                video.title.clone(),
+                output_path,
+                format,
+            )
+            .await?;
+        self.task(&video.id)
+    }
+
+    fn task(&self, video_id: &str) -> Result<DownloadTask> {
+        self.queue
+            .get_tasks()
+            .into_iter()
+            .find(|task| task.video_id == video_id)
+            .with_context(|| format!("Tâche introuvable: {video_id}"))
+    }
+}
+
+/// Ouvre le socket, en remplaçant celui laissé par une instance arrêtée
+fn bind(path: &std::path::Path) -> Result<UnixListener> {
+    if path.exists() {
+        if std::os::unix::net::UnixStream::connect(path).is_ok() {
+            anyhow::bail!("{} est déjà utilisé par une autre instance", path.display());
+        }
+        std::fs::remove_file(path)?;
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let listener = UnixListener::bind(path)?;
+    // Réservé à l'utilisateur courant
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|error| RpcError::new(INVALID_PARAMS, error.to_string()))
+}
+
+fn to_value(value: impl Serialize) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|error| RpcError::new(SERVER_ERROR, error.to_string()))
+}
+
+async fn write_line(writer: &mut UnixStream, value: &impl Serialize) -> Result<()> {
+    let mut line = serde_json::to_vec(value)?;
+    line.push(b'\n');
+    writer.write_all(&line).await?;
+    Ok(())
+}
+
+/// Ouvre un lien dans cette instance une fois l'API de contrôle démarrée
+pub fn open_in_background(cx: &App, url: String) {
+    cx.background_executor()
+        .spawn(async move {
+            let result = smol::unblock(move || call("open", serde_json::json!({ "url": url })));
+            if let Err(error) = result.await {
+                tracing::warn!("Impossible d'ouvrir le lien: {}", error);
+            }
+        })
+        .detach();
+}
+
+/// Appelle une méthode de l'instance en cours.
+/// Retourne `None` si aucune instance n'écoute sur le socket de contrôle.
+pub fn call(method: &str, params: Value) -> Result<Option<Value>> {
+    let Ok(stream) = std::os::unix::net::UnixStream::connect(socket_path()) else {
+        return Ok(None);
+    };
+
+    let request = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": method,
+        "params": params,
+    });
+    let mut line = serde_json::to_vec(&request)?;
+    line.push(b'\n');
+    std::io::Write::write_all(&mut &stream, &line)?;
+
+    let mut response = String::new();
+    std::io::BufRead::read_line(&mut std::io::BufReader::new(&stream), &mut response)?;
+    let mut response: serde_json::Map<String, Value> =
+        serde_json::from_str(&response).context("Réponse invalide de l'instance en cours")?;
+
+    if let Some(error) = response.remove("error") {
+        let error: RpcError = serde_json::from_value(error)?;
+        anyhow::bail!(error.message);
+    }
+    Ok(Some(response.remove("result").unwrap_or(Value::Null)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_serialization() {
+        let ok = Response::new(Value::from(1), Ok(Value::from("ok")));
+        assert_eq!(
+            serde_json::to_value(&ok).unwrap(),
+            serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": "ok"})
+        );
+
+        let error = Response::new(
+            Value::from(2),
+            Err(RpcError::new(METHOD_NOT_FOUND, "Méthode inconnue: x")),
+        );
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "error": {"code": -32601, "message": "Méthode inconnue: x"},
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_params() {
+        let params: EnqueueParams =
+            parse_params(serde_json::json!({"url": "https://youtu.be/abc"})).unwrap();
+        assert_eq!(params.url, "https://youtu.be/abc");
+        assert!(params.name.is_none());
+
+        let error = parse_params::<TaskParams>(serde_json::json!({}))
+            .err()
+            .unwrap();
+        assert_eq!(error.code, INVALID_PARAMS);
+    }
+}
@@ -0,0 +1,99 @@
+use crate::archive::DownloadArchive;
+use crate::channels::ChannelStore;
+use crate::config::Config;
+use crate::control::{self, ControlServer};
+use crate::downloader_queue::DownloadQueue;
+use crate::monitor::ChannelMonitor;
+use crate::paths;
+use crate::scanner::VideoScanner;
+use anyhow::{Context, Result};
+use async_signal::{Signal, Signals};
+use futures::StreamExt;
+use gpui::{App, Application};
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Délai laissé aux téléchargements en cours pour s'interrompre proprement
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Journal par défaut du mode sans interface
+pub fn default_log_file() -> PathBuf {
+    paths::state_dir().join("ndownloader.log")
+}
+
+/// Redirige les logs vers un fichier, ouvert en ajout
+pub fn init_logging(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Impossible d'ouvrir le journal {}", path.display()))?;
+
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .with_ansi(false)
+        .with_writer(Mutex::new(file))
+        .init();
+
+    Ok(())
+}
+
+/// Lance la surveillance des chaînes et la queue de téléchargement sans fenêtre,
+/// jusqu'à réception de SIGTERM ou SIGINT
+pub fn run(config: Config, url: Option<String>) -> Result<()> {
+    // Enregistrés avant le démarrage pour ne manquer aucun signal
+    let mut signals = Signals::new([Signal::Term, Signal::Int])
+        .context("Impossible d'intercepter les signaux d'arrêt")?;
+
+    tracing::info!("Démarrage en mode sans interface");
+
+    Application::headless().run(move |cx: &mut App| {
+        let archive = Arc::new(DownloadArchive::open(config.data_dir.join("archive.json")));
+        let queue = Arc::new(DownloadQueue::with_config(
+            cx,
+            config.queue_config(),
+            archive.clone(),
+        ));
+        let scanner = Arc::new(VideoScanner::new(&config, archive));
+
+        let channels = ChannelStore::from_config(&config);
+        ChannelMonitor::start(cx, channels.clone(), scanner.clone(), queue.clone());
+        // La surveillance relit les chaînes à chaque passage: rien à recharger
+        let _notices = ControlServer::start(cx, channels.clone(), scanner, queue.clone());
+        if let Some(url) = url {
+            control::open_in_background(cx, url);
+        }
+
+        let monitored = channels
+            .load()
+            .iter()
+            .filter(|channel| channel.monitor.is_active())
+            .count();
+        tracing::info!(
+            "{} chaîne(s) surveillée(s), {} tâche(s) dans la queue",
+            monitored,
+            queue.get_tasks().len()
+        );
+
+        cx.spawn(async move |cx| {
+            match signals.next().await {
+                Some(Ok(signal)) => tracing::info!("Signal {:?} reçu, arrêt en cours", signal),
+                Some(Err(error)) => tracing::error!("Lecture des signaux impossible: {}", error),
+                None => {}
+            }
+
+            // Les téléchargements interrompus reprendront au prochain lancement
+            queue.shutdown(SHUTDOWN_TIMEOUT).await;
+            tracing::info!("Arrêt terminé");
+            cx.update(|cx| cx.quit()).ok();
+        })
+        .detach();
+    });
+
+    Ok(())
+}
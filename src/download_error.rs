@@ -0,0 +1,179 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Erreur de téléchargement classée à partir de la sortie d'erreur de yt-dlp
+#[derive(Debug, Clone, PartialEq, Error, Serialize, Deserialize)]
+pub enum DownloadError {
+    #[error("Erreur réseau ou délai dépassé")]
+    Network,
+    #[error("Trop de requêtes, limite atteinte (HTTP 403/429)")]
+    RateLimited,
+    #[error("Vidéo bloquée dans cette région")]
+    GeoBlocked,
+    #[error("Vidéo réservée aux membres ou abonnés")]
+    MembersOnly,
+    #[error("Vidéo privée ou supprimée")]
+    Unavailable,
+    #[error("Espace disque insuffisant")]
+    DiskFull,
+    #[error("Échec de la fusion audio/vidéo par ffmpeg")]
+    MergeFailed,
+    #[error("yt-dlp a échoué: {0}")]
+    Other(String),
+}
+
+impl DownloadError {
+    /// Classe une erreur d'après la sortie d'erreur de yt-dlp
+    pub fn classify(stderr: &str) -> Self {
+        let lower = stderr.to_lowercase();
+        let contains_any = |patterns: &[&str]| patterns.iter().any(|p| lower.contains(p));
+
+        if contains_any(&["no space left on device", "errno 28", "disk quota exceeded"]) {
+            Self::DiskFull
+        } else if contains_any(&[
+            "members-only",
+            "members only",
+            "join this channel",
+            "subscriber-only",
+            "subscribers only",
+            "only available to subscribers",
+        ]) {
+            Self::MembersOnly
+        } else if contains_any(&[
+            "available in your country",
+            "blocked it in your country",
+            "geo restrict",
+            "geo-restrict",
+        ]) {
+            Self::GeoBlocked
+        } else if contains_any(&[
+            "private video",
+            "video unavailable",
+            "has been removed",
+            "has been deleted",
+            "does not exist",
+            "http error 404",
+        ]) {
+            Self::Unavailable
+        } else if contains_any(&[
+            "http error 429",
+            "too many requests",
+            "http error 403",
+            "rate limit",
+            "rate-limit",
+        ]) {
+            Self::RateLimited
+        } else if contains_any(&[
+            "postprocessing",
+            "conversion failed",
+            "ffmpeg exited",
+            "merging",
+        ]) {
+            Self::MergeFailed
+        } else if contains_any(&[
+            "timed out",
+            "timeout",
+            "connection reset",
+            "connection refused",
+            "network is unreachable",
+            "temporary failure in name resolution",
+            "getaddrinfo failed",
+            "incomplete read",
+            "remote end closed connection",
+            "urlopen error",
+            "http error 5",
+        ]) {
+            Self::Network
+        } else {
+            let last_error = stderr
+                .lines()
+                .rev()
+                .find(|line| line.contains("ERROR"))
+                .or_else(|| stderr.lines().rev().find(|line| !line.trim().is_empty()))
+                .unwrap_or("code de sortie non nul");
+            Self::Other(last_error.trim().to_string())
+        }
+    }
+
+    /// Convertit une erreur système (lancement du processus, création de dossier)
+    pub fn from_io(error: std::io::Error) -> Self {
+        if error.kind() == std::io::ErrorKind::StorageFull {
+            Self::DiskFull
+        } else {
+            Self::Other(error.to_string())
+        }
+    }
+
+    /// Indique si l'erreur est passagère et mérite une nouvelle tentative.
+    /// Un échec de ffmpeg se reproduirait à l'identique.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::Network | Self::RateLimited)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_transient_errors() {
+        let error = DownloadError::classify(
+            "ERROR: unable to download video data: HTTP Error 429: Too Many Requests",
+        );
+        assert_eq!(error, DownloadError::RateLimited);
+        assert!(error.is_transient());
+
+        let error = DownloadError::classify("ERROR: Read timed out.");
+        assert_eq!(error, DownloadError::Network);
+        assert!(error.is_transient());
+
+        let error = DownloadError::classify(
+            "ERROR: unable to download video data: HTTP Error 503: Service Unavailable",
+        );
+        assert_eq!(error, DownloadError::Network);
+        assert!(error.is_transient());
+    }
+
+    #[test]
+    fn test_classify_permanent_errors() {
+        let error = DownloadError::classify(
+            "ERROR: [youtube] abc: Join this channel to get access to members-only content",
+        );
+        assert_eq!(error, DownloadError::MembersOnly);
+        assert!(!error.is_transient());
+
+        let error = DownloadError::classify("ERROR: [youtube] abc: Private video");
+        assert_eq!(error, DownloadError::Unavailable);
+
+        let error = DownloadError::classify(
+            "ERROR: [youtube] abc: The uploader has not made this video available in your country",
+        );
+        assert_eq!(error, DownloadError::GeoBlocked);
+
+        let error = DownloadError::classify(
+            "ERROR: unable to write data: [Errno 28] No space left on device",
+        );
+        assert_eq!(error, DownloadError::DiskFull);
+
+        let error = DownloadError::classify("ERROR: Postprocessing: Conversion failed!");
+        assert_eq!(error, DownloadError::MergeFailed);
+        assert!(!error.is_transient());
+
+        let error = DownloadError::classify(
+            "ERROR: [generic] abc: Unable to download webpage: HTTP Error 404: Not Found",
+        );
+        assert!(!error.is_transient());
+    }
+
+    #[test]
+    fn test_classify_unknown_error_keeps_last_line() {
+        let error = DownloadError::classify(
+            "WARNING: something\nERROR: Unsupported URL: https://example.com\n",
+        );
+        assert_eq!(
+            error,
+            DownloadError::Other("ERROR: Unsupported URL: https://example.com".to_string())
+        );
+        assert!(!error.is_transient());
+    }
+}
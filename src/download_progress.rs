@@ -0,0 +1,337 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Préfixe des lignes de progression produites par `--progress-template`
+const DOWNLOAD_PREFIX: &str = "[ndownloader:download] ";
+/// Préfixe des lignes de post-traitement (fusion, conversion...)
+const POSTPROCESS_PREFIX: &str = "[ndownloader:postprocess] ";
+
+/// Gabarit de progression JSON passé à yt-dlp.
+/// Les champs absents sont remplacés par "NA" par yt-dlp.
+pub const DOWNLOAD_TEMPLATE: &str = concat!(
+    "download:[ndownloader:download] {",
+    "\"status\":%(progress.status)j,",
+    "\"downloaded_bytes\":%(progress.downloaded_bytes)j,",
+    "\"total_bytes\":%(progress.total_bytes)j,",
+    "\"total_bytes_estimate\":%(progress.total_bytes_estimate)j,",
+    "\"speed\":%(progress.speed)j,",
+    "\"eta\":%(progress.eta)j,",
+    "\"fragment_index\":%(progress.fragment_index)j,",
+    "\"fragment_count\":%(progress.fragment_count)j,",
+    "\"vcodec\":%(info.vcodec)j,",
+    "\"acodec\":%(info.acodec)j",
+    "}"
+);
+
+/// Gabarit de progression du post-traitement passé à yt-dlp
+pub const POSTPROCESS_TEMPLATE: &str = concat!(
+    "postprocess:[ndownloader:postprocess] {",
+    "\"status\":%(progress.status)j,",
+    "\"postprocessor\":%(progress.postprocessor)j",
+    "}"
+);
+
+/// Part de la progression globale réservée au post-traitement
+const POSTPROCESS_WEIGHT: f32 = 0.05;
+/// Part du flux vidéo quand vidéo et audio sont téléchargés séparément
+const VIDEO_WEIGHT: f32 = 0.8;
+
+/// Flux en cours de téléchargement
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum StreamKind {
+    Video,
+    Audio,
+    /// Fichier unique contenant audio et vidéo
+    Combined,
+}
+
+/// Progression d'un flux telle que rapportée par yt-dlp
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DownloadProgress {
+    pub downloaded_bytes: u64,
+    /// Taille totale, exacte ou estimée
+    pub total_bytes: Option<u64>,
+    /// Vitesse en octets par seconde
+    pub speed: Option<f64>,
+    /// Temps restant en secondes
+    pub eta: Option<u64>,
+    pub fragment_index: Option<u32>,
+    pub fragment_count: Option<u32>,
+    pub stream: StreamKind,
+    pub finished: bool,
+}
+
+impl DownloadProgress {
+    /// Avancement du flux courant entre 0.0 et 1.0
+    pub fn fraction(&self) -> f32 {
+        if self.finished {
+            return 1.0;
+        }
+
+        let fraction = match (self.total_bytes, self.fragment_index, self.fragment_count) {
+            (Some(total), _, _) if total > 0 => self.downloaded_bytes as f32 / total as f32,
+            (_, Some(index), Some(count)) if count > 0 => index as f32 / count as f32,
+            _ => 0.0,
+        };
+        fraction.clamp(0.0, 1.0)
+    }
+}
+
+/// Ligne de sortie de yt-dlp reconnue
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProgressLine {
+    Download(DownloadProgress),
+    /// Nom du post-processeur en cours (ex: "Merger")
+    PostProcess(String),
+}
+
+#[derive(Deserialize)]
+struct RawDownload {
+    status: Value,
+    downloaded_bytes: Value,
+    total_bytes: Value,
+    total_bytes_estimate: Value,
+    speed: Value,
+    eta: Value,
+    fragment_index: Value,
+    fragment_count: Value,
+    vcodec: Value,
+    acodec: Value,
+}
+
+#[derive(Deserialize)]
+struct RawPostProcess {
+    postprocessor: Value,
+}
+
+/// Analyse une ligne produite par les gabarits de progression
+pub fn parse_progress_line(line: &str) -> Option<ProgressLine> {
+    if let Some(json) = line.strip_prefix(DOWNLOAD_PREFIX) {
+        let raw: RawDownload = serde_json::from_str(json).ok()?;
+        let has_codec = |codec: &Value| codec.as_str().is_some_and(|c| c != "none" && c != "NA");
+        let stream = match (has_codec(&raw.vcodec), has_codec(&raw.acodec)) {
+            (true, false) => StreamKind::Video,
+            (false, true) => StreamKind::Audio,
+            _ => StreamKind::Combined,
+        };
+
+        return Some(ProgressLine::Download(DownloadProgress {
+            downloaded_bytes: as_u64(&raw.downloaded_bytes).unwrap_or(0),
+            total_bytes: as_u64(&raw.total_bytes).or_else(|| as_u64(&raw.total_bytes_estimate)),
+            speed: raw.speed.as_f64(),
+            eta: as_u64(&raw.eta),
+            fragment_index: as_u64(&raw.fragment_index).map(|i| i as u32),
+            fragment_count: as_u64(&raw.fragment_count).map(|c| c as u32),
+            stream,
+            finished: raw.status.as_str() == Some("finished"),
+        }));
+    }
+
+    if let Some(json) = line.strip_prefix(POSTPROCESS_PREFIX) {
+        let raw: RawPostProcess = serde_json::from_str(json).ok()?;
+        let name = raw.postprocessor.as_str().unwrap_or("NA").to_string();
+        return Some(ProgressLine::PostProcess(name));
+    }
+
+    None
+}
+
+/// Les valeurs numériques peuvent être des flottants (taille estimée) ou "NA"
+fn as_u64(value: &Value) -> Option<u64> {
+    value
+        .as_u64()
+        .or_else(|| value.as_f64().map(|v| v.max(0.0) as u64))
+}
+
+/// Calcule la progression globale sur les flux vidéo, audio et le post-traitement
+#[derive(Debug)]
+pub struct ProgressTracker {
+    /// Faux pour un téléchargement audio seul, dont l'audio compte pour 100%
+    has_video: bool,
+    video: f32,
+    audio: f32,
+    combined: Option<f32>,
+    postprocessing: bool,
+}
+
+impl Default for ProgressTracker {
+    fn default() -> Self {
+        Self::new(true)
+    }
+}
+
+impl ProgressTracker {
+    pub fn new(has_video: bool) -> Self {
+        Self {
+            has_video,
+            video: 0.0,
+            audio: 0.0,
+            combined: None,
+            postprocessing: false,
+        }
+    }
+
+    pub fn update(&mut self, progress: &DownloadProgress) {
+        let fraction = progress.fraction();
+        match progress.stream {
+            StreamKind::Video => self.video = fraction,
+            StreamKind::Audio => {
+                // yt-dlp télécharge la vidéo avant l'audio
+                if self.has_video {
+                    self.video = 1.0;
+                }
+                self.audio = fraction;
+            }
+            StreamKind::Combined => self.combined = Some(fraction),
+        }
+    }
+
+    pub fn start_postprocess(&mut self) {
+        self.postprocessing = true;
+    }
+
+    pub fn overall(&self) -> f32 {
+        if self.postprocessing {
+            return 1.0 - POSTPROCESS_WEIGHT;
+        }
+
+        let download = match self.combined {
+            Some(fraction) => fraction,
+            None if !self.has_video => self.audio,
+            None => self.video * VIDEO_WEIGHT + self.audio * (1.0 - VIDEO_WEIGHT),
+        };
+        download * (1.0 - POSTPROCESS_WEIGHT)
+    }
+}
+
+/// Formate une taille en octets (ex: "2.5 MiB")
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+/// Formate une vitesse en octets par seconde (ex: "2.5 MiB/s")
+pub fn format_speed(bytes_per_second: f64) -> String {
+    format!("{}/s", format_size(bytes_per_second.max(0.0) as u64))
+}
+
+/// Formate un temps restant en secondes (ex: "05:30" ou "1:02:03")
+pub fn format_eta(seconds: u64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, (seconds % 3600) / 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes:02}:{seconds:02}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_video_progress() {
+        let line = r#"[ndownloader:download] {"status":"downloading","downloaded_bytes":45200000,"total_bytes":100000000,"total_bytes_estimate":"NA","speed":2621440.0,"eta":330,"fragment_index":"NA","fragment_count":"NA","vcodec":"avc1.640028","acodec":"none"}"#;
+
+        let Some(ProgressLine::Download(progress)) = parse_progress_line(line) else {
+            panic!("ligne non reconnue");
+        };
+
+        assert_eq!(progress.stream, StreamKind::Video);
+        assert_eq!(progress.total_bytes, Some(100_000_000));
+        assert_eq!(progress.speed, Some(2621440.0));
+        assert_eq!(progress.eta, Some(330));
+        assert!((progress.fraction() - 0.452).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_parse_fragmented_progress_with_estimate() {
+        let line = r#"[ndownloader:download] {"status":"downloading","downloaded_bytes":1048576,"total_bytes":"NA","total_bytes_estimate":"NA","speed":"NA","eta":"NA","fragment_index":12,"fragment_count":340,"vcodec":"avc1","acodec":"mp4a.40.2"}"#;
+
+        let Some(ProgressLine::Download(progress)) = parse_progress_line(line) else {
+            panic!("ligne non reconnue");
+        };
+
+        assert_eq!(progress.stream, StreamKind::Combined);
+        assert_eq!(progress.total_bytes, None);
+        assert_eq!(progress.speed, None);
+        assert_eq!(progress.eta, None);
+        assert_eq!(progress.fragment_index, Some(12));
+        assert!((progress.fraction() - 12.0 / 340.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_parse_postprocess_line() {
+        let line = r#"[ndownloader:postprocess] {"status":"started","postprocessor":"Merger"}"#;
+        assert_eq!(
+            parse_progress_line(line),
+            Some(ProgressLine::PostProcess("Merger".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_unrelated_line() {
+        assert_eq!(parse_progress_line("[info] Downloading video..."), None);
+        assert_eq!(
+            parse_progress_line("[ndownloader:download] pas du json"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_tracker_combines_streams() {
+        let mut tracker = ProgressTracker::default();
+        let mut progress = DownloadProgress {
+            downloaded_bytes: 50,
+            total_bytes: Some(100),
+            speed: None,
+            eta: None,
+            fragment_index: None,
+            fragment_count: None,
+            stream: StreamKind::Video,
+            finished: false,
+        };
+
+        tracker.update(&progress);
+        assert!((tracker.overall() - 0.5 * VIDEO_WEIGHT * 0.95).abs() < 0.001);
+
+        progress.stream = StreamKind::Audio;
+        tracker.update(&progress);
+        let expected = (VIDEO_WEIGHT + 0.5 * (1.0 - VIDEO_WEIGHT)) * 0.95;
+        assert!((tracker.overall() - expected).abs() < 0.001);
+
+        tracker.start_postprocess();
+        assert!((tracker.overall() - 0.95).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_tracker_audio_only() {
+        let mut tracker = ProgressTracker::new(false);
+        tracker.update(&DownloadProgress {
+            downloaded_bytes: 50,
+            total_bytes: Some(100),
+            speed: None,
+            eta: None,
+            fragment_index: None,
+            fragment_count: None,
+            stream: StreamKind::Audio,
+            finished: false,
+        });
+        assert!((tracker.overall() - 0.5 * 0.95).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_format_speed_and_eta() {
+        assert_eq!(format_speed(2621440.0), "2.5 MiB/s");
+        assert_eq!(format_speed(512.0), "512.0 B/s");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GiB");
+        assert_eq!(format_eta(330), "05:30");
+        assert_eq!(format_eta(3723), "1:02:03");
+    }
+}
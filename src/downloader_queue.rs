@@ -1,247 +1,1293 @@
+use crate::archive::DownloadArchive;
+use crate::download_error::DownloadError;
+use crate::download_progress::{self, DownloadProgress, ProgressLine, ProgressTracker};
+use crate::extractors;
+use crate::paths;
 use anyhow::Result;
-use futures::channel::mpsc;
+use chrono::{DateTime, Utc};
+use futures::channel::{mpsc, oneshot};
+use futures::future::{self, Either};
 use futures::{SinkExt, StreamExt};
-use gpui::AppContext;
+use gpui::{App, BackgroundExecutor};
 use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
 use smol::io::{AsyncBufReadExt, BufReader};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-#[derive(Debug, Clone)]
+/// Nombre de lignes de stderr conservées pour classer une erreur
+const STDERR_TAIL_LINES: usize = 50;
+
+/// Délai laissé à yt-dlp et ffmpeg pour s'arrêter avant SIGKILL
+const STOP_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownloadTask {
     pub video_id: String,
+    /// Plateforme au sens de l'archive (clé d'extracteur yt-dlp)
+    #[serde(default)]
+    pub platform: String,
     pub video_url: String,
     pub title: String,
     pub output_path: PathBuf,
+    #[serde(default)]
+    pub format: FormatProfile,
     pub status: DownloadStatus,
+    #[serde(default)]
     pub progress: f32,
-    pub speed: Option<String>,
-    pub eta: Option<String>,
+    /// Vitesse en octets par seconde
+    #[serde(skip)]
+    pub speed: Option<f64>,
+    /// Temps restant en secondes
+    #[serde(skip)]
+    pub eta: Option<u64>,
+    /// Emplacement du pool occupé par la tâche (None tant qu'elle attend)
+    #[serde(skip)]
+    pub slot: Option<usize>,
+    /// Reprendre les données partielles existantes (--continue)
+    #[serde(default)]
+    pub resume: bool,
+    /// Nombre de lancements de yt-dlp pour cette tâche
+    #[serde(default)]
+    pub attempts: u32,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub started_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Dernière erreur rencontrée
+    #[serde(default)]
+    pub error: Option<DownloadError>,
+    /// Prochaine tentative après une erreur passagère
+    #[serde(default)]
+    pub retry_at: Option<DateTime<Utc>>,
+}
+
+impl DownloadTask {
+    /// Hôte de la vidéo, utilisé pour les limites par plateforme
+    pub fn host(&self) -> String {
+        host_of(&self.video_url)
+    }
+
+    /// Tâche terminée, échouée ou annulée, qui ne reste qu'en historique
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.status,
+            DownloadStatus::Completed | DownloadStatus::Failed(_) | DownloadStatus::Cancelled
+        )
+    }
+
+    /// Annule une tâche en attente ou en pause et supprime ses fichiers partiels.
+    /// Retourne false si la tâche n'est pas dans l'un de ces états.
+    pub fn cancel_pending(&mut self) -> bool {
+        if !matches!(self.status, DownloadStatus::Queued | DownloadStatus::Paused) {
+            return false;
+        }
+        self.status = DownloadStatus::Cancelled;
+        self.finished_at = Some(Utc::now());
+        DownloadQueue::remove_partial_files(&self.output_path);
+        true
+    }
+
+    /// Remet en attente une tâche échouée ou annulée, avec un nouveau
+    /// compteur de tentatives. Retourne false pour les autres états.
+    pub fn reset_for_retry(&mut self) -> bool {
+        if !matches!(
+            self.status,
+            DownloadStatus::Failed(_) | DownloadStatus::Cancelled
+        ) {
+            return false;
+        }
+        self.status = DownloadStatus::Queued;
+        self.progress = 0.0;
+        self.resume = true;
+        self.attempts = 0;
+        self.finished_at = None;
+        self.error = None;
+        self.retry_at = None;
+        true
+    }
+}
+
+/// Qualité demandée à yt-dlp
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FormatProfile {
+    #[default]
+    Best,
+    Max1080p,
+    Max720p,
+    Max480p,
+    AudioOnly,
+}
+
+impl FormatProfile {
+    /// Sélecteur passé à `yt-dlp -f`
+    pub fn ytdlp_format(self) -> &'static str {
+        match self {
+            FormatProfile::Best => "bestvideo[ext=mp4]+bestaudio[ext=m4a]/best[ext=mp4]",
+            FormatProfile::Max1080p => {
+                "bestvideo[ext=mp4][height<=1080]+bestaudio[ext=m4a]/best[ext=mp4][height<=1080]"
+            }
+            FormatProfile::Max720p => {
+                "bestvideo[ext=mp4][height<=720]+bestaudio[ext=m4a]/best[ext=mp4][height<=720]"
+            }
+            FormatProfile::Max480p => {
+                "bestvideo[ext=mp4][height<=480]+bestaudio[ext=m4a]/best[ext=mp4][height<=480]"
+            }
+            FormatProfile::AudioOnly => "bestaudio[ext=m4a]/bestaudio",
+        }
+    }
+
+    /// Hauteur d'image maximale, None sans limite ou pour l'audio seul
+    pub fn max_height(self) -> Option<u32> {
+        match self {
+            FormatProfile::Max1080p => Some(1080),
+            FormatProfile::Max720p => Some(720),
+            FormatProfile::Max480p => Some(480),
+            FormatProfile::Best | FormatProfile::AudioOnly => None,
+        }
+    }
+
+    /// Le format comporte un flux vidéo
+    pub fn has_video(self) -> bool {
+        self != FormatProfile::AudioOnly
+    }
+
+    /// Extension du fichier produit
+    pub fn extension(self) -> &'static str {
+        match self {
+            FormatProfile::AudioOnly => "m4a",
+            _ => "mp4",
+        }
+    }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum DownloadStatus {
     Queued,
     Downloading,
     Completed,
     Failed(String),
+    Paused,
+    Cancelled,
 }
 
-pub struct DownloadQueue {
+/// Raison de l'arrêt d'un téléchargement en cours
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StopReason {
+    Pause,
+    Cancel,
+    /// Arrêt de l'application: la tâche reprendra au prochain lancement
+    Shutdown,
+}
+
+enum DownloadOutcome {
+    Completed,
+    Stopped(StopReason),
+}
+
+/// Événement émis par la queue à chaque changement d'état d'une tâche
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadEvent {
+    pub video_id: String,
+    pub video_url: String,
+    pub title: String,
+    pub kind: DownloadEventKind,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum DownloadEventKind {
+    Queued,
+    Started {
+        slot: usize,
+        attempt: u32,
+    },
+    Progress {
+        /// Progression globale (flux vidéo, audio et post-traitement)
+        progress: f32,
+        /// Détail du flux en cours
+        detail: DownloadProgress,
+    },
+    /// Post-traitement: fusion des flux audio et vidéo par ffmpeg
+    Merging,
+    Completed {
+        output_path: PathBuf,
+    },
+    Failed {
+        reason: String,
+    },
+    /// Erreur passagère, nouvelle tentative programmée
+    Retrying {
+        reason: String,
+        delay_secs: u64,
+    },
+    Paused,
+    Cancelled,
+    /// Interrompu par l'arrêt de l'application, reprise au prochain lancement
+    Interrupted,
+}
+
+impl DownloadEvent {
+    fn new(task: &DownloadTask, kind: DownloadEventKind) -> Self {
+        Self {
+            video_id: task.video_id.clone(),
+            video_url: task.video_url.clone(),
+            title: task.title.clone(),
+            kind,
+        }
+    }
+}
+
+/// Limites de concurrence du pool de téléchargement
+#[derive(Debug, Clone)]
+pub struct QueueConfig {
+    /// Nombre maximal de téléchargements simultanés, toutes plateformes confondues
+    pub max_concurrent: usize,
+    /// Nombre maximal de téléchargements simultanés par hôte (ex: "twitch.tv" => 2)
+    pub per_host_limits: HashMap<String, usize>,
+    /// Fichier où la queue est sauvegardée entre deux lancements
+    pub state_file: PathBuf,
+    /// Politique de nouvelle tentative pour les erreurs passagères
+    pub retry: RetryPolicy,
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: 3,
+            per_host_limits: HashMap::from([("twitch.tv".to_string(), 2)]),
+            state_file: default_state_file(),
+            retry: RetryPolicy::default(),
+        }
+    }
+}
+
+/// Nouvelles tentatives avec délai exponentiel
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Nombre maximal de lancements de yt-dlp pour une même tâche
+    pub max_attempts: u32,
+    /// Délai avant la première nouvelle tentative
+    pub base_delay: Duration,
+    /// Délai maximal entre deux tentatives
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_secs(30),
+            max_delay: Duration::from_secs(30 * 60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Délai avant la prochaine tentative, ou None si l'erreur est définitive
+    fn next_delay(&self, error: &DownloadError, attempts: u32) -> Option<Duration> {
+        if !error.is_transient() || attempts >= self.max_attempts {
+            return None;
+        }
+
+        let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+        Some(self.base_delay.saturating_mul(factor).min(self.max_delay))
+    }
+}
+
+impl QueueConfig {
+    fn host_limit(&self, host: &str) -> usize {
+        self.per_host_limits
+            .iter()
+            .find(|(pattern, _)| host == pattern.as_str() || host.ends_with(&format!(".{pattern}")))
+            .map(|(_, limit)| *limit)
+            .unwrap_or(self.max_concurrent)
+    }
+}
+
+enum QueueMessage {
+    /// Une tâche vient d'être ajoutée
+    Enqueued,
+    /// Un worker a libéré son emplacement
+    Finished,
+}
+
+/// État partagé entre la queue, le dispatcher et les workers
+#[derive(Clone)]
+struct QueueShared {
     tasks: Arc<Mutex<Vec<DownloadTask>>>,
-    tx: mpsc::UnboundedSender<DownloadTask>,
+    /// Canal d'arrêt de chaque processus yt-dlp en cours, par video_id
+    controls: Arc<Mutex<HashMap<String, oneshot::Sender<StopReason>>>>,
+    tx: mpsc::UnboundedSender<QueueMessage>,
+    config: Arc<QueueConfig>,
+    executor: BackgroundExecutor,
+    subscribers: Arc<Mutex<Vec<mpsc::UnboundedSender<DownloadEvent>>>>,
+    archive: Arc<DownloadArchive>,
+    /// Plus aucun téléchargement ne démarre une fois l'arrêt demandé
+    shutting_down: Arc<AtomicBool>,
+}
+
+impl QueueShared {
+    fn update_task(&self, video_id: &str, f: impl FnOnce(&mut DownloadTask)) {
+        let mut tasks = self.tasks.lock();
+        if let Some(task) = tasks.iter_mut().find(|t| t.video_id == video_id) {
+            f(task);
+        }
+    }
+
+    /// Attribue les emplacements libres et enregistre le canal d'arrêt de
+    /// chaque tâche démarrée sans relâcher le verrou des tâches: une pause ou
+    /// une annulation demandée entre-temps trouve toujours son canal.
+    fn start_ready_tasks(&self) -> Vec<(DownloadTask, oneshot::Receiver<StopReason>)> {
+        let mut tasks = self.tasks.lock();
+        let ready = DownloadQueue::assign_slots(&mut tasks, &self.config, Utc::now());
+        let mut controls = self.controls.lock();
+        ready
+            .into_iter()
+            .map(|task| {
+                let (stop_tx, stop_rx) = oneshot::channel();
+                controls.insert(task.video_id.clone(), stop_tx);
+                (task, stop_rx)
+            })
+            .collect()
+    }
+
+    fn notify(&self, message: QueueMessage) {
+        self.tx.unbounded_send(message).ok();
+    }
+
+    /// Diffuse un événement à tous les abonnés encore actifs
+    fn emit(&self, task: &DownloadTask, kind: DownloadEventKind) {
+        let event = DownloadEvent::new(task, kind);
+        self.subscribers
+            .lock()
+            .retain(|subscriber| subscriber.unbounded_send(event.clone()).is_ok());
+    }
+
+    /// Sauvegarde la queue sur disque (écriture atomique)
+    fn persist(&self) {
+        let tasks = self.tasks.lock().clone();
+        if let Err(error) = save_tasks(&self.config.state_file, &tasks) {
+            tracing::error!("Impossible de sauvegarder la queue: {}", error);
+        }
+    }
+}
+
+pub struct DownloadQueue {
+    shared: QueueShared,
 }
 
 impl DownloadQueue {
-    pub fn new(cx: &impl AppContext) -> Self {
-        let tasks: Arc<Mutex<Vec<DownloadTask>>> = Arc::new(Mutex::new(Vec::new()));
-        let (tx, mut rx) = mpsc::unbounded::<DownloadTask>();
+    pub fn with_config(cx: &App, config: QueueConfig, archive: Arc<DownloadArchive>) -> Self {
+        let (tx, mut rx) = mpsc::unbounded::<QueueMessage>();
 
-        let tasks_clone = tasks.clone();
+        // Recharger la queue du lancement précédent
+        let tasks = match load_tasks(&config.state_file) {
+            Ok(tasks) => restore_tasks(tasks),
+            Err(error) => {
+                tracing::debug!("Pas de queue sauvegardée: {}", error);
+                Vec::new()
+            }
+        };
+        let has_pending = tasks.iter().any(|t| t.status == DownloadStatus::Queued);
 
-        // Worker qui traite les téléchargements
-        cx.background_spawn(async move {
-            while let Some(mut task) = rx.next().await {
-                tracing::info!("Début du téléchargement: {}", task.title);
+        let shared = QueueShared {
+            tasks: Arc::new(Mutex::new(tasks)),
+            controls: Arc::new(Mutex::new(HashMap::new())),
+            tx,
+            config: Arc::new(config),
+            executor: cx.background_executor().clone(),
+            subscribers: Arc::new(Mutex::new(Vec::new())),
+            archive,
+            shutting_down: Arc::new(AtomicBool::new(false)),
+        };
 
-                // Mettre à jour le statut
-                task.status = DownloadStatus::Downloading;
-                {
-                    let mut tasks_lock = tasks_clone.lock();
-                    if let Some(t) = tasks_lock.iter_mut().find(|t| t.video_id == task.video_id) {
-                        t.status = DownloadStatus::Downloading;
+        if has_pending {
+            shared.notify(QueueMessage::Enqueued);
+        }
+
+        let shared_clone = shared.clone();
+
+        // Dispatcher qui attribue les emplacements libres aux tâches en attente
+        cx.background_executor()
+            .spawn(async move {
+                while rx.next().await.is_some() {
+                    if shared_clone.shutting_down.load(Ordering::SeqCst) {
+                        continue;
+                    }
+                    let ready = shared_clone.start_ready_tasks();
+                    if !ready.is_empty() {
+                        shared_clone.persist();
+                    }
+                    for (task, stop_rx) in ready {
+                        shared_clone.emit(
+                            &task,
+                            DownloadEventKind::Started {
+                                slot: task.slot.unwrap_or_default(),
+                                attempt: task.attempts,
+                            },
+                        );
+                        Self::spawn_worker(task, stop_rx, shared_clone.clone());
                     }
                 }
+            })
+            .detach();
+
+        Self { shared }
+    }
+
+    /// Démarre le téléchargement d'une tâche dans son emplacement
+    fn spawn_worker(
+        mut task: DownloadTask,
+        stop_rx: oneshot::Receiver<StopReason>,
+        shared: QueueShared,
+    ) {
+        let executor = shared.executor.clone();
+        executor
+            .spawn(async move {
+                tracing::info!(
+                    "Début du téléchargement: {} (emplacement {:?})",
+                    task.title,
+                    task.slot
+                );
 
                 // Télécharger la vidéo avec mise à jour de progression
-                let tasks_for_progress = tasks_clone.clone();
-                let video_id = task.video_id.clone();
-
-                match Self::download_video(&task, move |progress, speed, eta| {
-                    let mut tasks_lock = tasks_for_progress.lock();
-                    if let Some(t) = tasks_lock.iter_mut().find(|t| t.video_id == video_id) {
-                        t.progress = progress;
-                        t.speed = speed;
-                        t.eta = eta;
+                let shared_for_progress = shared.clone();
+                let task_for_progress = task.clone();
+                let mut tracker = ProgressTracker::new(task.format.has_video());
+
+                let event = match Self::download_video(&task, stop_rx, move |line| match line {
+                    ProgressLine::Download(detail) => {
+                        tracker.update(&detail);
+                        let progress = tracker.overall();
+                        shared_for_progress.update_task(&task_for_progress.video_id, |t| {
+                            t.progress = progress;
+                            t.speed = detail.speed;
+                            t.eta = detail.eta;
+                        });
+                        shared_for_progress.emit(
+                            &task_for_progress,
+                            DownloadEventKind::Progress { progress, detail },
+                        );
+                    }
+                    ProgressLine::PostProcess(postprocessor) => {
+                        tracing::debug!("Post-traitement: {}", postprocessor);
+                        tracker.start_postprocess();
+                        let progress = tracker.overall();
+                        shared_for_progress.update_task(&task_for_progress.video_id, |t| {
+                            t.progress = progress;
+                            t.speed = None;
+                            t.eta = None;
+                        });
+                        shared_for_progress.emit(&task_for_progress, DownloadEventKind::Merging);
                     }
                 })
                 .await
                 {
-                    Ok(_) => {
+                    Ok(DownloadOutcome::Completed) => {
                         tracing::info!("Téléchargement terminé: {}", task.title);
                         task.status = DownloadStatus::Completed;
                         task.progress = 1.0;
+                        Self::archive_download(&shared, &task).await;
+                        DownloadEventKind::Completed {
+                            output_path: task.output_path.clone(),
+                        }
                     }
-                    Err(e) => {
-                        tracing::error!("Erreur téléchargement {}: {}", task.title, e);
-                        task.status = DownloadStatus::Failed(e.to_string());
+                    Ok(DownloadOutcome::Stopped(StopReason::Pause)) => {
+                        tracing::info!("Téléchargement en pause: {}", task.title);
+                        task.status = DownloadStatus::Paused;
+                        DownloadEventKind::Paused
                     }
-                }
+                    Ok(DownloadOutcome::Stopped(StopReason::Cancel)) => {
+                        tracing::info!("Téléchargement annulé: {}", task.title);
+                        task.status = DownloadStatus::Cancelled;
+                        Self::remove_partial_files(&task.output_path);
+                        DownloadEventKind::Cancelled
+                    }
+                    Ok(DownloadOutcome::Stopped(StopReason::Shutdown)) => {
+                        tracing::info!("Téléchargement interrompu: {}", task.title);
+                        task.status = DownloadStatus::Queued;
+                        task.resume = true;
+                        DownloadEventKind::Interrupted
+                    }
+                    Err(error) => {
+                        tracing::error!("Erreur téléchargement {}: {}", task.title, error);
+                        let reason = error.to_string();
+                        let event = match shared.config.retry.next_delay(&error, task.attempts) {
+                            Some(delay) => {
+                                tracing::info!(
+                                    "Nouvelle tentative pour {} dans {}s",
+                                    task.title,
+                                    delay.as_secs()
+                                );
+                                task.status = DownloadStatus::Queued;
+                                task.resume = true;
+                                task.retry_at = chrono::Duration::from_std(delay)
+                                    .ok()
+                                    .map(|delay| Utc::now() + delay);
+                                Self::schedule_retry(&shared, delay);
+                                DownloadEventKind::Retrying {
+                                    reason,
+                                    delay_secs: delay.as_secs(),
+                                }
+                            }
+                            None => {
+                                task.status = DownloadStatus::Failed(reason.clone());
+                                DownloadEventKind::Failed { reason }
+                            }
+                        };
+                        task.error = Some(error);
+                        event
+                    }
+                };
 
-                // Mettre à jour le statut final
-                {
-                    let mut tasks_lock = tasks_clone.lock();
-                    if let Some(t) = tasks_lock.iter_mut().find(|t| t.video_id == task.video_id) {
-                        t.status = task.status.clone();
-                        t.progress = task.progress;
+                shared.controls.lock().remove(&task.video_id);
+
+                // Mettre à jour le statut final et libérer l'emplacement
+                shared.update_task(&task.video_id, |t| {
+                    t.status = task.status.clone();
+                    if task.status == DownloadStatus::Completed {
+                        t.progress = 1.0;
                     }
-                }
+                    t.slot = None;
+                    t.resume = task.resume;
+                    t.error = task.error.clone();
+                    t.retry_at = task.retry_at;
+                    if !matches!(task.status, DownloadStatus::Paused | DownloadStatus::Queued) {
+                        t.finished_at = Some(Utc::now());
+                    }
+                });
+                shared.persist();
+                shared.emit(&task, event);
+
+                shared.notify(QueueMessage::Finished);
+            })
+            .detach();
+    }
+
+    /// Ajoute la vidéo terminée à l'archive de téléchargement
+    async fn archive_download(shared: &QueueShared, task: &DownloadTask) {
+        if task.platform.is_empty() {
+            return;
+        }
+
+        let archive = shared.archive.clone();
+        let (platform, video_id, path) = (
+            task.platform.clone(),
+            task.video_id.clone(),
+            task.output_path.clone(),
+        );
+        let result = smol::unblock(move || archive.record_file(&platform, &video_id, &path)).await;
+        if let Err(error) = result {
+            tracing::error!("Impossible d'archiver {}: {}", task.title, error);
+        }
+    }
+
+    /// Réveille le dispatcher une fois le délai avant nouvelle tentative écoulé
+    fn schedule_retry(shared: &QueueShared, delay: Duration) {
+        let shared_clone = shared.clone();
+        shared
+            .executor
+            .spawn(async move {
+                shared_clone.executor.timer(delay).await;
+                shared_clone.notify(QueueMessage::Enqueued);
+            })
+            .detach();
+    }
+
+    /// Attribue un emplacement libre aux tâches en attente, dans l'ordre de la queue,
+    /// en respectant la limite globale et les limites par hôte.
+    /// Retourne les tâches à démarrer.
+    fn assign_slots(
+        tasks: &mut [DownloadTask],
+        config: &QueueConfig,
+        now: DateTime<Utc>,
+    ) -> Vec<DownloadTask> {
+        let mut used_slots: Vec<usize> = tasks.iter().filter_map(|t| t.slot).collect();
+        let mut per_host: HashMap<String, usize> = HashMap::new();
+        for task in tasks.iter().filter(|t| t.slot.is_some()) {
+            *per_host.entry(task.host()).or_default() += 1;
+        }
+
+        let mut ready = Vec::new();
+        for task in tasks.iter_mut() {
+            if task.status != DownloadStatus::Queued || task.slot.is_some() {
+                continue;
+            }
+            if task.retry_at.is_some_and(|retry_at| retry_at > now) {
+                continue;
+            }
+
+            let Some(slot) = (0..config.max_concurrent).find(|i| !used_slots.contains(i)) else {
+                break;
+            };
+
+            let host = task.host();
+            let running = per_host.get(&host).copied().unwrap_or(0);
+            if running >= config.host_limit(&host) {
+                continue;
             }
-        })
-        .detach();
 
-        Self { tasks, tx }
+            task.slot = Some(slot);
+            task.status = DownloadStatus::Downloading;
+            task.attempts += 1;
+            task.started_at = Some(now);
+            task.retry_at = None;
+            used_slots.push(slot);
+            *per_host.entry(host).or_default() += 1;
+            ready.push(task.clone());
+        }
+
+        ready
     }
 
     /// Ajoute une tâche de téléchargement à la queue
     pub async fn add_download(
         &self,
         video_id: String,
+        platform: String,
         video_url: String,
         title: String,
         output_path: PathBuf,
+        format: FormatProfile,
     ) -> Result<()> {
         let task = DownloadTask {
-            video_id: video_id.clone(),
+            video_id,
+            platform,
             video_url,
             title,
             output_path,
+            format,
             status: DownloadStatus::Queued,
             progress: 0.0,
             speed: None,
             eta: None,
+            slot: None,
+            resume: false,
+            attempts: 0,
+            created_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+            error: None,
+            retry_at: None,
         };
 
         // Ajouter à la liste
-        {
-            let mut tasks = self.tasks.lock();
-            tasks.push(task.clone());
-        }
+        Self::insert_task(&mut self.shared.tasks.lock(), task.clone())?;
+        self.shared.emit(&task, DownloadEventKind::Queued);
+        self.shared.persist();
 
-        // Envoyer au worker
-        self.tx.clone().send(task).await?;
+        // Prévenir le dispatcher
+        self.shared.tx.clone().send(QueueMessage::Enqueued).await?;
 
         Ok(())
     }
 
+    /// Ajoute une tâche en fin de queue. Une tâche terminée pour la même vidéo
+    /// est remplacée, pour qu'un video_id désigne toujours une seule tâche.
+    fn insert_task(tasks: &mut Vec<DownloadTask>, task: DownloadTask) -> Result<()> {
+        if let Some(index) = tasks.iter().position(|t| t.video_id == task.video_id) {
+            if !tasks[index].is_finished() {
+                anyhow::bail!("Vidéo déjà dans la queue: {}", task.title);
+            }
+            tasks.remove(index);
+        }
+        tasks.push(task);
+        Ok(())
+    }
+
     /// Obtient la liste de toutes les tâches
     pub fn get_tasks(&self) -> Vec<DownloadTask> {
-        let tasks = self.tasks.lock();
+        let tasks = self.shared.tasks.lock();
         tasks.clone()
     }
 
+    /// S'abonne aux événements de la queue (progression, fin, échec...)
+    pub fn subscribe(&self) -> mpsc::UnboundedReceiver<DownloadEvent> {
+        let (tx, rx) = mpsc::unbounded();
+        self.shared.subscribers.lock().push(tx);
+        rx
+    }
+
+    /// Annule un téléchargement: arrête yt-dlp et supprime les fichiers partiels
+    pub fn cancel(&self, video_id: &str) {
+        {
+            let mut tasks = self.shared.tasks.lock();
+            let Some(task) = tasks.iter_mut().find(|t| t.video_id == video_id) else {
+                return;
+            };
+
+            if task.status == DownloadStatus::Downloading {
+                return self.stop(video_id, StopReason::Cancel);
+            }
+            if !task.cancel_pending() {
+                return;
+            }
+            self.shared.emit(task, DownloadEventKind::Cancelled);
+        }
+
+        self.shared.persist();
+    }
+
+    /// Relance un téléchargement échoué ou annulé
+    pub fn retry(&self, video_id: &str) {
+        {
+            let mut tasks = self.shared.tasks.lock();
+            let Some(task) = tasks.iter_mut().find(|t| t.video_id == video_id) else {
+                return;
+            };
+            if !task.reset_for_retry() {
+                return;
+            }
+            self.shared.emit(task, DownloadEventKind::Queued);
+        }
+
+        self.shared.persist();
+        self.shared.notify(QueueMessage::Enqueued);
+    }
+
+    /// Met un téléchargement en pause en conservant les données partielles
+    pub fn pause(&self, video_id: &str) {
+        {
+            let mut tasks = self.shared.tasks.lock();
+            let Some(task) = tasks.iter_mut().find(|t| t.video_id == video_id) else {
+                return;
+            };
+
+            match task.status {
+                DownloadStatus::Downloading => return self.stop(video_id, StopReason::Pause),
+                DownloadStatus::Queued => {
+                    task.status = DownloadStatus::Paused;
+                    self.shared.emit(task, DownloadEventKind::Paused);
+                }
+                _ => return,
+            }
+        }
+
+        self.shared.persist();
+    }
+
+    /// Reprend un téléchargement en pause là où il s'était arrêté
+    pub fn resume(&self, video_id: &str) {
+        {
+            let mut tasks = self.shared.tasks.lock();
+            let Some(task) = tasks
+                .iter_mut()
+                .find(|t| t.video_id == video_id && t.status == DownloadStatus::Paused)
+            else {
+                return;
+            };
+            task.status = DownloadStatus::Queued;
+            task.resume = true;
+            self.shared.emit(task, DownloadEventKind::Queued);
+        }
+
+        self.shared.persist();
+        self.shared.notify(QueueMessage::Enqueued);
+    }
+
+    /// Arrête la queue: plus aucun téléchargement ne démarre et ceux en cours
+    /// sont interrompus en conservant leurs données partielles. La queue est
+    /// sauvegardée pour reprendre au prochain lancement.
+    pub async fn shutdown(&self, timeout: Duration) {
+        self.shared.shutting_down.store(true, Ordering::SeqCst);
+
+        let controls: Vec<_> = self.shared.controls.lock().drain().collect();
+        if !controls.is_empty() {
+            tracing::info!("Interruption de {} téléchargement(s)", controls.len());
+        }
+        for (_, stop_tx) in controls {
+            stop_tx.send(StopReason::Shutdown).ok();
+        }
+
+        // Laisser les workers enregistrer l'état de leur tâche
+        let deadline = Instant::now() + timeout;
+        while self.shared.tasks.lock().iter().any(|t| t.slot.is_some()) {
+            if Instant::now() >= deadline {
+                tracing::warn!("Arrêt forcé: des téléchargements n'ont pas répondu");
+                break;
+            }
+            self.shared.executor.timer(Duration::from_millis(100)).await;
+        }
+
+        self.shared.persist();
+    }
+
+    fn stop(&self, video_id: &str, reason: StopReason) {
+        if let Some(stop_tx) = self.shared.controls.lock().remove(video_id) {
+            stop_tx.send(reason).ok();
+        }
+    }
+
+    /// Supprime les fichiers temporaires laissés par yt-dlp (.part, .ytdl)
+    fn remove_partial_files(output_path: &Path) {
+        let (Some(parent), Some(stem)) = (output_path.parent(), output_path.file_stem()) else {
+            return;
+        };
+        let stem = stem.to_string_lossy().to_string();
+
+        let Ok(entries) = std::fs::read_dir(parent) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if Self::is_partial_file(&name, &stem) {
+                if let Err(error) = std::fs::remove_file(entry.path()) {
+                    tracing::warn!("Impossible de supprimer {}: {}", name, error);
+                }
+            }
+        }
+    }
+
+    fn is_partial_file(name: &str, stem: &str) -> bool {
+        name.starts_with(&format!("{stem}.")) && (name.contains(".part") || name.ends_with(".ytdl"))
+    }
+
     /// Télécharge une vidéo avec yt-dlp
-    async fn download_video<F>(task: &DownloadTask, mut on_progress: F) -> Result<()>
+    async fn download_video<F>(
+        task: &DownloadTask,
+        stop_rx: oneshot::Receiver<StopReason>,
+        mut on_update: F,
+    ) -> Result<DownloadOutcome, DownloadError>
     where
-        F: FnMut(f32, Option<String>, Option<String>) + Send + 'static,
+        F: FnMut(ProgressLine) + Send + 'static,
     {
         // Créer le dossier de sortie si nécessaire
         if let Some(parent) = task.output_path.parent() {
-            smol::fs::create_dir_all(parent).await?;
+            smol::fs::create_dir_all(parent)
+                .await
+                .map_err(DownloadError::from_io)?;
         }
 
-        let output_template = task.output_path.to_string_lossy().to_string();
+        let output_template = output_template(&task.output_path);
 
-        let mut child = smol::process::Command::new("yt-dlp")
+        // Groupe de processus dédié, pour que l'arrêt atteigne aussi ffmpeg
+        let mut command = std::process::Command::new("yt-dlp");
+        command.process_group(0);
+        let mut command = smol::process::Command::from(command);
+        command
             .arg("--newline")
+            .arg("--progress-template")
+            .arg(download_progress::DOWNLOAD_TEMPLATE)
+            .arg("--progress-template")
+            .arg(download_progress::POSTPROCESS_TEMPLATE);
+        if task.resume {
+            command.arg("--continue");
+        }
+
+        let mut child = command
             .arg("-o")
             .arg(&output_template)
-            .arg("-f")
-            .arg("bestvideo[ext=mp4]+bestaudio[ext=m4a]/best[ext=mp4]")
+            .args(extractors::download_args(&task.video_url, task.format))
             .arg(&task.video_url)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .spawn()?;
+            .spawn()
+            .map_err(DownloadError::from_io)?;
 
         // Lire la sortie pour extraire la progression
-        if let Some(stdout) = child.stdout.take() {
+        let stdout = child.stdout.take();
+        let read_progress = async move {
+            let Some(stdout) = stdout else {
+                return;
+            };
             let reader = BufReader::new(stdout);
             let mut lines = reader.lines();
 
             while let Some(line) = lines.next().await {
-                if let Ok(line) = line {
-                    let (progress, speed, eta) = Self::parse_download_line(&line);
-                    if let Some(p) = progress {
-                        on_progress(p, speed, eta);
+                if let Some(update) = line
+                    .ok()
+                    .as_deref()
+                    .and_then(download_progress::parse_progress_line)
+                {
+                    on_update(update);
+                }
+            }
+        };
+
+        // Conserver la fin de stderr pour classer l'erreur éventuelle
+        let stderr = child.stderr.take();
+        let read_errors = async move {
+            let mut tail = std::collections::VecDeque::new();
+            if let Some(stderr) = stderr {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Some(Ok(line)) = lines.next().await {
+                    if tail.len() == STDERR_TAIL_LINES {
+                        tail.pop_front();
                     }
+                    tail.push_back(line);
                 }
             }
-        }
+            Vec::from(tail).join("\n")
+        };
+
+        let read_output = std::pin::pin!(future::join(read_progress, read_errors));
+
+        // Arrêter le processus si une pause ou une annulation est demandée
+        let ((), stderr) = match future::select(read_output, stop_rx).await {
+            Either::Left((output, _)) => output,
+            Either::Right((Ok(reason), _)) => {
+                Self::terminate(&mut child).await;
+                return Ok(DownloadOutcome::Stopped(reason));
+            }
+            Either::Right((Err(_), read_output)) => read_output.await,
+        };
 
-        let status = child.status().await?;
+        let status = child.status().await.map_err(DownloadError::from_io)?;
         if !status.success() {
-            anyhow::bail!("yt-dlp a échoué");
+            return Err(DownloadError::classify(&stderr));
         }
 
-        Ok(())
+        Ok(DownloadOutcome::Completed)
     }
 
-    fn parse_download_line(line: &str) -> (Option<f32>, Option<String>, Option<String>) {
-        if !line.contains("[download]") {
-            return (None, None, None);
+    /// Interrompt yt-dlp et ses processus enfants (SIGINT au groupe, comme un
+    /// Ctrl+C qui conserve les fichiers partiels), puis SIGKILL sans réponse
+    async fn terminate(child: &mut smol::process::Child) {
+        let group = -(child.id() as libc::pid_t);
+        // SAFETY: kill n'accède à aucune mémoire, le groupe est celui de yt-dlp
+        unsafe { libc::kill(group, libc::SIGINT) };
+
+        let exited = smol::future::or(
+            async {
+                child.status().await.ok();
+                true
+            },
+            async {
+                smol::Timer::after(STOP_TIMEOUT).await;
+                false
+            },
+        )
+        .await;
+        if !exited {
+            tracing::warn!("yt-dlp ne répond pas, arrêt forcé");
+            // SAFETY: voir ci-dessus
+            unsafe { libc::kill(group, libc::SIGKILL) };
+            child.status().await.ok();
         }
+    }
+}
 
-        let mut progress = None;
-        let mut speed = None;
-        let mut eta = None;
+/// Chemin de sortie passé à `yt-dlp -o`. Les `%` (titre, nom de chaîne ou
+/// modèle de nom) sont doublés pour ne pas être lus comme des champs du modèle
+/// de yt-dlp: le fichier doit arriver à `output_path`.
+fn output_template(output_path: &Path) -> String {
+    output_path.to_string_lossy().replace('%', "%%")
+}
 
-        // Parse progress: "[download]  45.2% of ..."
-        if line.contains('%') {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            for (i, part) in parts.iter().enumerate() {
-                if part.ends_with('%') {
-                    if let Ok(val) = part.trim_end_matches('%').parse::<f32>() {
-                        progress = Some(val / 100.0);
-                    }
-                }
-                // Parse speed: "at 2.5MiB/s"
-                if *part == "at" && i + 1 < parts.len() {
-                    speed = Some(parts[i + 1].to_string());
-                }
-                // Parse ETA: "ETA 05:30"
-                if *part == "ETA" && i + 1 < parts.len() {
-                    eta = Some(parts[i + 1].to_string());
-                }
-            }
-        }
+/// Emplacement par défaut de la queue sauvegardée
+fn default_state_file() -> PathBuf {
+    paths::data_dir().join("queue.json")
+}
 
-        (progress, speed, eta)
+pub fn load_tasks(path: &Path) -> Result<Vec<DownloadTask>> {
+    let content = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+pub fn save_tasks(path: &Path, tasks: &[DownloadTask]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
     }
+    let content = serde_json::to_string_pretty(tasks)?;
+    let tmp_path = path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, content)?;
+    std::fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+/// Prépare les tâches rechargées: les téléchargements interrompus repartent
+/// en attente avec reprise, les tâches terminées restent en historique
+fn restore_tasks(tasks: Vec<DownloadTask>) -> Vec<DownloadTask> {
+    tasks
+        .into_iter()
+        .map(|mut task| {
+            if task.status == DownloadStatus::Downloading {
+                task.status = DownloadStatus::Queued;
+                task.resume = true;
+            }
+            // Les tentatives en attente repartent dès le démarrage
+            task.retry_at = None;
+            task
+        })
+        .collect()
+}
+
+/// Extrait l'hôte d'une URL, sans le préfixe "www." ou "m."
+fn host_of(url: &str) -> String {
+    let without_scheme = url.split("://").nth(1).unwrap_or(url);
+    let host = without_scheme
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    host.trim_start_matches("www.")
+        .trim_start_matches("m.")
+        .to_string()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn make_task(video_id: &str, url: &str) -> DownloadTask {
+        DownloadTask {
+            video_id: video_id.to_string(),
+            platform: "youtube".to_string(),
+            video_url: url.to_string(),
+            title: video_id.to_string(),
+            output_path: PathBuf::from(format!("/tmp/{video_id}.mp4")),
+            format: FormatProfile::Best,
+            status: DownloadStatus::Queued,
+            progress: 0.0,
+            speed: None,
+            eta: None,
+            slot: None,
+            resume: false,
+            attempts: 0,
+            created_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+            error: None,
+            retry_at: None,
+        }
+    }
+
+    #[test]
+    fn test_host_of() {
+        assert_eq!(host_of("https://www.twitch.tv/videos/123"), "twitch.tv");
+        assert_eq!(host_of("https://m.youtube.com/watch?v=abc"), "youtube.com");
+        assert_eq!(host_of("https://youtu.be/abc"), "youtu.be");
+    }
+
+    #[test]
+    fn test_assign_slots_respects_global_limit() {
+        let config = QueueConfig {
+            max_concurrent: 2,
+            per_host_limits: HashMap::new(),
+            ..QueueConfig::default()
+        };
+        let mut tasks = vec![
+            make_task("a", "https://youtube.com/watch?v=a"),
+            make_task("b", "https://youtube.com/watch?v=b"),
+            make_task("c", "https://youtube.com/watch?v=c"),
+        ];
+
+        let ready = DownloadQueue::assign_slots(&mut tasks, &config, Utc::now());
+
+        assert_eq!(ready.len(), 2);
+        assert_eq!(tasks[0].slot, Some(0));
+        assert_eq!(tasks[1].slot, Some(1));
+        assert_eq!(tasks[2].slot, None);
+        assert_eq!(tasks[2].status, DownloadStatus::Queued);
+    }
+
+    #[test]
+    fn test_assign_slots_respects_host_limit() {
+        let config = QueueConfig {
+            max_concurrent: 3,
+            per_host_limits: HashMap::from([("twitch.tv".to_string(), 1)]),
+            ..QueueConfig::default()
+        };
+        let mut tasks = vec![
+            make_task("a", "https://www.twitch.tv/videos/1"),
+            make_task("b", "https://www.twitch.tv/videos/2"),
+            make_task("c", "https://youtube.com/watch?v=c"),
+        ];
+
+        let ready = DownloadQueue::assign_slots(&mut tasks, &config, Utc::now());
+
+        let started: Vec<&str> = ready.iter().map(|t| t.video_id.as_str()).collect();
+        assert_eq!(started, vec!["a", "c"]);
+        assert_eq!(tasks[1].slot, None);
+        assert_eq!(tasks[2].slot, Some(1));
+    }
+
+    #[test]
+    fn test_assign_slots_reuses_freed_slot() {
+        let config = QueueConfig {
+            max_concurrent: 2,
+            per_host_limits: HashMap::new(),
+            ..QueueConfig::default()
+        };
+        let mut tasks = vec![
+            make_task("a", "https://youtube.com/watch?v=a"),
+            make_task("b", "https://youtube.com/watch?v=b"),
+            make_task("c", "https://youtube.com/watch?v=c"),
+        ];
+        DownloadQueue::assign_slots(&mut tasks, &config, Utc::now());
+
+        // Le premier téléchargement se termine
+        tasks[0].status = DownloadStatus::Completed;
+        tasks[0].slot = None;
+
+        let ready = DownloadQueue::assign_slots(&mut tasks, &config, Utc::now());
+
+        assert_eq!(ready.len(), 1);
+        assert_eq!(tasks[2].slot, Some(0));
+    }
+
     #[test]
-    fn test_parse_progress() {
-        let line = "[download]  45.2% of 100.00MiB at 2.5MiB/s ETA 05:30";
-        let (progress, speed, eta) = DownloadQueue::parse_download_line(line);
+    fn test_assign_slots_skips_paused_tasks() {
+        let config = QueueConfig::default();
+        let mut tasks = vec![
+            make_task("a", "https://youtube.com/watch?v=a"),
+            make_task("b", "https://youtube.com/watch?v=b"),
+        ];
+        tasks[0].status = DownloadStatus::Paused;
+
+        let ready = DownloadQueue::assign_slots(&mut tasks, &config, Utc::now());
+
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].video_id, "b");
+        assert_eq!(tasks[0].slot, None);
+    }
 
-        assert!(progress.is_some());
-        assert!((progress.unwrap() - 0.452).abs() < 0.001);
-        assert_eq!(speed, Some("2.5MiB/s".to_string()));
-        assert_eq!(eta, Some("05:30".to_string()));
+    #[test]
+    fn test_is_partial_file() {
+        assert!(DownloadQueue::is_partial_file("video.mp4.part", "video"));
+        assert!(DownloadQueue::is_partial_file(
+            "video.f137.mp4.part",
+            "video"
+        ));
+        assert!(DownloadQueue::is_partial_file(
+            "video.mp4.part-Frag12",
+            "video"
+        ));
+        assert!(DownloadQueue::is_partial_file("video.mp4.ytdl", "video"));
+        assert!(!DownloadQueue::is_partial_file("video.mp4", "video"));
+        assert!(!DownloadQueue::is_partial_file("other.mp4.part", "video"));
+        assert!(!DownloadQueue::is_partial_file("video 2.mp4.part", "video"));
     }
 
     #[test]
-    fn test_parse_progress_no_speed() {
-        let line = "[download]  75.0% of 100.00MiB";
-        let (progress, speed, eta) = DownloadQueue::parse_download_line(line);
+    fn test_insert_task_replaces_finished_task() {
+        let mut tasks = vec![make_task("a", "https://youtube.com/watch?v=a")];
+        assert!(DownloadQueue::insert_task(&mut tasks, make_task("a", "u")).is_err());
+
+        tasks[0].status = DownloadStatus::Failed("erreur".to_string());
+        tasks.push(make_task("b", "https://youtube.com/watch?v=b"));
+        DownloadQueue::insert_task(&mut tasks, make_task("a", "u")).unwrap();
 
-        assert_eq!(progress, Some(0.75));
-        assert_eq!(speed, None);
-        assert_eq!(eta, None);
+        let ids: Vec<&str> = tasks.iter().map(|t| t.video_id.as_str()).collect();
+        assert_eq!(ids, ["b", "a"]);
+        assert_eq!(tasks[1].status, DownloadStatus::Queued);
+    }
+
+    #[test]
+    fn test_output_template_escapes_percent() {
+        assert_eq!(
+            output_template(Path::new("/videos/100% réussi %(id)s.mp4")),
+            "/videos/100%% réussi %%(id)s.mp4"
+        );
     }
 
     #[test]
-    fn test_parse_non_download_line() {
-        let line = "[info] Downloading video...";
-        let (progress, speed, eta) = DownloadQueue::parse_download_line(line);
+    fn test_restore_tasks() {
+        let mut downloading = make_task("a", "https://youtube.com/watch?v=a");
+        downloading.status = DownloadStatus::Downloading;
+        let mut completed = make_task("b", "https://youtube.com/watch?v=b");
+        completed.status = DownloadStatus::Completed;
+        let mut failed = make_task("c", "https://youtube.com/watch?v=c");
+        failed.status = DownloadStatus::Failed("erreur".to_string());
+
+        let tasks = restore_tasks(vec![downloading, completed, failed]);
+
+        assert_eq!(tasks[0].status, DownloadStatus::Queued);
+        assert!(tasks[0].resume);
+        assert_eq!(tasks[1].status, DownloadStatus::Completed);
+        assert_eq!(
+            tasks[2].status,
+            DownloadStatus::Failed("erreur".to_string())
+        );
+    }
+
+    #[test]
+    fn test_reset_for_retry() {
+        let mut failed = make_task("a", "https://youtube.com/watch?v=a");
+        failed.status = DownloadStatus::Failed("erreur".to_string());
+        failed.attempts = 5;
+        assert!(failed.reset_for_retry());
+        assert_eq!(failed.status, DownloadStatus::Queued);
+        assert_eq!(failed.attempts, 0);
+        assert!(failed.resume);
+
+        let mut completed = make_task("b", "https://youtube.com/watch?v=b");
+        completed.status = DownloadStatus::Completed;
+        assert!(!completed.reset_for_retry());
+        assert_eq!(completed.status, DownloadStatus::Completed);
+    }
+
+    #[test]
+    fn test_save_and_load_tasks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("queue.json");
+
+        let mut task = make_task("a", "https://youtube.com/watch?v=a");
+        task.status = DownloadStatus::Downloading;
+        task.attempts = 2;
+        task.slot = Some(1);
+        task.speed = Some(2621440.0);
+
+        save_tasks(&path, &[task]).unwrap();
+        let loaded = load_tasks(&path).unwrap();
+
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].video_id, "a");
+        assert_eq!(loaded[0].status, DownloadStatus::Downloading);
+        assert_eq!(loaded[0].attempts, 2);
+        // L'état d'exécution n'est pas sauvegardé
+        assert_eq!(loaded[0].slot, None);
+        assert_eq!(loaded[0].speed, None);
+    }
+
+    #[test]
+    fn test_assign_slots_waits_for_retry_delay() {
+        let config = QueueConfig::default();
+        let mut tasks = vec![make_task("a", "https://youtube.com/watch?v=a")];
+        let now = Utc::now();
+        tasks[0].retry_at = Some(now + chrono::Duration::seconds(30));
+
+        assert!(DownloadQueue::assign_slots(&mut tasks, &config, now).is_empty());
+
+        let later = now + chrono::Duration::seconds(31);
+        let ready = DownloadQueue::assign_slots(&mut tasks, &config, later);
+        assert_eq!(ready.len(), 1);
+        assert_eq!(tasks[0].retry_at, None);
+        assert_eq!(tasks[0].attempts, 1);
+    }
+
+    #[test]
+    fn test_retry_policy_backoff() {
+        let policy = RetryPolicy {
+            max_attempts: 4,
+            base_delay: Duration::from_secs(10),
+            max_delay: Duration::from_secs(25),
+        };
 
-        assert_eq!(progress, None);
-        assert_eq!(speed, None);
-        assert_eq!(eta, None);
+        assert_eq!(
+            policy.next_delay(&DownloadError::Network, 1),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(
+            policy.next_delay(&DownloadError::Network, 2),
+            Some(Duration::from_secs(20))
+        );
+        // Plafonné au délai maximal
+        assert_eq!(
+            policy.next_delay(&DownloadError::RateLimited, 3),
+            Some(Duration::from_secs(25))
+        );
+        // Nombre maximal de tentatives atteint
+        assert_eq!(policy.next_delay(&DownloadError::Network, 4), None);
+        // Erreur définitive: pas de nouvelle tentative
+        assert_eq!(policy.next_delay(&DownloadError::MembersOnly, 1), None);
     }
 }
@@ -0,0 +1,877 @@
+use crate::channels::{Channel, Platform};
+use crate::downloader_queue::FormatProfile;
+use crate::media_url::{is_identifier, UrlKind, UrlParts};
+use crate::scanner::VideoMetadata;
+
+/// Prise en charge d'une plateforme: reconnaissance des liens, identité des
+/// chaînes, liste des vidéos et téléchargement avec yt-dlp
+pub trait Extractor: Send + Sync {
+    fn platform(&self) -> Platform;
+
+    /// Nom affiché de la plateforme
+    fn label(&self) -> &'static str;
+
+    /// Hôte pris en charge (en minuscules, sans "www.")
+    fn handles_host(&self, _host: &str) -> bool {
+        false
+    }
+
+    /// Ce que désigne un lien de la plateforme
+    fn classify(&self, url: &UrlParts) -> Option<UrlKind>;
+
+    /// URL canonique de la chaîne désignée par le lien, sans paramètres ni onglet
+    fn channel_url(&self, kind: &UrlKind, url: &UrlParts) -> Option<String>;
+
+    /// Identifiant stable de la chaîne quand le lien le contient déjà. Sinon il
+    /// est résolu par yt-dlp (voir `VideoScanner::fill_channel_id`).
+    fn channel_id(&self, _kind: &UrlKind, _url: &UrlParts) -> Option<String> {
+        None
+    }
+
+    /// URL dont yt-dlp liste les vidéos de la chaîne
+    fn listing_url(&self, channel: &Channel) -> String {
+        channel.url.clone()
+    }
+
+    /// La chaîne publie un flux Atom de ses dernières vidéos (voir `feeds`)
+    fn has_feed(&self) -> bool {
+        false
+    }
+
+    /// Corrige une vidéo lue dans une liste à plat
+    fn normalize_video(&self, video: VideoMetadata) -> VideoMetadata {
+        video
+    }
+
+    /// Arguments de sélection du format passés à yt-dlp
+    fn download_args(&self, format: FormatProfile) -> Vec<String> {
+        vec!["-f".to_string(), format.ytdlp_format().to_string()]
+    }
+}
+
+pub struct YouTube;
+
+pub struct Twitch;
+
+pub struct Kick;
+
+pub struct Vimeo;
+
+pub struct PeerTube;
+
+pub struct Dailymotion;
+
+pub struct Odysee;
+
+/// Tout autre site dont yt-dlp sait lister les vidéos
+pub struct Generic;
+
+/// Plateformes reconnues à leur hôte
+static EXTRACTORS: &[&dyn Extractor] = &[
+    &YouTube,
+    &Twitch,
+    &Kick,
+    &Vimeo,
+    &PeerTube,
+    &Dailymotion,
+    &Odysee,
+];
+
+/// Instances PeerTube connues. Les chemins PeerTube (`/c/`, `/a/`, `/w/`) sont
+/// trop courants pour reconnaître une instance inconnue: elle passe par `Generic`.
+const PEERTUBE_INSTANCES: &[&str] = &[
+    "diode.zone",
+    "framatube.org",
+    "kolektiva.media",
+    "makertube.net",
+    "peertube.tv",
+    "spectra.video",
+    "tilvids.com",
+    "tube.tchncs.de",
+    "video.blender.org",
+    "video.ploud.fr",
+];
+
+/// Extracteur de l'hôte d'un lien
+pub fn for_host(host: &str) -> Option<&'static dyn Extractor> {
+    EXTRACTORS
+        .iter()
+        .copied()
+        .find(|extractor| extractor.handles_host(host))
+}
+
+/// Extracteur d'un lien, par son hôte. `None` pour un site inconnu (voir `Generic`).
+pub fn for_url(url: &UrlParts) -> Option<&'static dyn Extractor> {
+    for_host(&url.host)
+}
+
+/// Arguments de format pour télécharger `video_url`. En audio seul, le flux
+/// retenu (opus, webm, TS...) est converti pour correspondre à l'extension .m4a
+/// du fichier de sortie.
+pub fn download_args(video_url: &str, format: FormatProfile) -> Vec<String> {
+    let mut args = for_url(&UrlParts::split(video_url))
+        .unwrap_or(&Generic)
+        .download_args(format);
+    if format == FormatProfile::AudioOnly {
+        args.extend(["-x", "--audio-format", "m4a"].map(String::from));
+    }
+    args
+}
+
+/// Formats des flux HLS qui mêlent déjà image et son (Twitch, Kick): pas de
+/// fusion bestvideo+bestaudio, seulement une limite de hauteur
+fn muxed_stream_args(format: FormatProfile) -> Vec<String> {
+    let selector = match (format, format.max_height()) {
+        (FormatProfile::AudioOnly, _) => "audio_only/bestaudio".to_string(),
+        (_, Some(height)) => format!("best[height<={height}]/best"),
+        (_, None) => "best".to_string(),
+    };
+    vec!["-f".to_string(), selector]
+}
+
+/// Sélection sans contrainte de conteneur, pour les sites dont les formats ne
+/// sont pas des paires mp4/m4a comme sur YouTube. La fusion se fait en mp4.
+fn any_container_args(format: FormatProfile) -> Vec<String> {
+    let limit = format
+        .max_height()
+        .map(|height| format!("[height<={height}]"))
+        .unwrap_or_default();
+    match format {
+        FormatProfile::AudioOnly => vec!["-f".to_string(), "bestaudio/best".to_string()],
+        _ => vec![
+            "-f".to_string(),
+            format!("bestvideo*{limit}+bestaudio/best{limit}"),
+            "--merge-output-format".to_string(),
+            "mp4".to_string(),
+        ],
+    }
+}
+
+/// Identifiant composé uniquement de chiffres (vidéos Vimeo)
+fn is_numeric(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|c| c.is_ascii_digit())
+}
+
+impl Extractor for YouTube {
+    fn platform(&self) -> Platform {
+        Platform::YouTube
+    }
+
+    fn label(&self) -> &'static str {
+        "YouTube"
+    }
+
+    fn handles_host(&self, host: &str) -> bool {
+        matches!(
+            host,
+            "youtube.com" | "m.youtube.com" | "music.youtube.com" | "youtu.be"
+        )
+    }
+
+    fn classify(&self, url: &UrlParts) -> Option<UrlKind> {
+        if url.host == "youtu.be" {
+            return match url.segments.as_slice() {
+                [id] => Some(UrlKind::Video(id.to_string())),
+                _ => None,
+            };
+        }
+
+        let kind = match url.segments.as_slice() {
+            [] | ["watch"] => match (url.param("v"), url.param("list")) {
+                (Some(video), _) => UrlKind::Video(video),
+                (None, Some(list)) => UrlKind::Playlist(list),
+                _ => return None,
+            },
+            ["playlist"] => UrlKind::Playlist(url.param("list")?),
+            ["shorts" | "live" | "embed" | "v", id] => UrlKind::Video(id.to_string()),
+            ["clip", id] => UrlKind::Clip(id.to_string()),
+            ["channel", id, "live"] => UrlKind::Live(id.to_string()),
+            ["channel", id, ..] => UrlKind::Channel(id.to_string()),
+            ["user" | "c", name, ..] => UrlKind::LegacyUser(name.to_string()),
+            [handle, rest @ ..] if handle.starts_with('@') => {
+                let handle = handle[1..].to_string();
+                match rest {
+                    ["live"] => UrlKind::Live(handle),
+                    _ => UrlKind::Handle(handle),
+                }
+            }
+            _ => return None,
+        };
+        Some(kind)
+    }
+
+    fn channel_url(&self, kind: &UrlKind, url: &UrlParts) -> Option<String> {
+        let channel_url = match kind {
+            UrlKind::Channel(id) => format!("https://www.youtube.com/channel/{id}"),
+            UrlKind::Handle(handle) => format!("https://www.youtube.com/@{handle}"),
+            UrlKind::LegacyUser(name) => {
+                format!("https://www.youtube.com/{}/{name}", url.segments[0])
+            }
+            UrlKind::Live(id) if url.segments[0] == "channel" => {
+                format!("https://www.youtube.com/channel/{id}")
+            }
+            UrlKind::Live(handle) => format!("https://www.youtube.com/@{handle}"),
+            _ => return None,
+        };
+        Some(channel_url)
+    }
+
+    fn channel_id(&self, kind: &UrlKind, _url: &UrlParts) -> Option<String> {
+        match kind {
+            UrlKind::Channel(id) => Some(id.clone()),
+            _ => None,
+        }
+    }
+
+    /// Listée à plat, la page de la chaîne donne ses onglets (vidéos, shorts,
+    /// directs) et non ses vidéos
+    fn listing_url(&self, channel: &Channel) -> String {
+        format!("{}/videos", channel.url.trim_end_matches('/'))
+    }
+
+    fn has_feed(&self) -> bool {
+        true
+    }
+}
+
+/// Chemins Twitch de premier niveau qui ne sont pas des chaînes
+const TWITCH_RESERVED: &[&str] = &[
+    "directory",
+    "downloads",
+    "drops",
+    "inventory",
+    "jobs",
+    "p",
+    "search",
+    "settings",
+    "subscriptions",
+    "turbo",
+    "videos",
+    "wallet",
+];
+
+impl Extractor for Twitch {
+    fn platform(&self) -> Platform {
+        Platform::Twitch
+    }
+
+    fn label(&self) -> &'static str {
+        "Twitch"
+    }
+
+    fn handles_host(&self, host: &str) -> bool {
+        matches!(
+            host,
+            "twitch.tv" | "m.twitch.tv" | "clips.twitch.tv" | "player.twitch.tv"
+        )
+    }
+
+    fn classify(&self, url: &UrlParts) -> Option<UrlKind> {
+        let kind = match (url.host.as_str(), url.segments.as_slice()) {
+            ("clips.twitch.tv", [slug]) => UrlKind::Clip(slug.to_string()),
+            ("clips.twitch.tv", _) => return None,
+            ("player.twitch.tv", _) => match (url.param("channel"), url.param("video")) {
+                (Some(channel), _) => UrlKind::Live(channel),
+                (None, Some(video)) => {
+                    UrlKind::TwitchVod(video.trim_start_matches('v').to_string())
+                }
+                _ => return None,
+            },
+            (_, ["videos", id]) => UrlKind::TwitchVod(id.trim_start_matches('v').to_string()),
+            (_, [_, "clip", slug]) => UrlKind::Clip(slug.to_string()),
+            (_, [_, "video" | "v", id]) => UrlKind::TwitchVod(id.to_string()),
+            (_, [name, ..]) if !TWITCH_RESERVED.contains(&name.to_ascii_lowercase().as_str()) => {
+                UrlKind::Channel(name.to_string())
+            }
+            _ => return None,
+        };
+        Some(kind)
+    }
+
+    fn channel_url(&self, kind: &UrlKind, _url: &UrlParts) -> Option<String> {
+        match kind {
+            UrlKind::Channel(name) | UrlKind::Live(name) => {
+                Some(format!("https://www.twitch.tv/{name}"))
+            }
+            _ => None,
+        }
+    }
+
+    /// Le login sert d'identifiant (sans tenir compte de la casse)
+    fn channel_id(&self, kind: &UrlKind, _url: &UrlParts) -> Option<String> {
+        match kind {
+            UrlKind::Channel(login) | UrlKind::Live(login) => Some(login.to_ascii_lowercase()),
+            _ => None,
+        }
+    }
+
+    /// La page de la chaîne ne liste que le direct: les VODs sont sous
+    /// `/videos`. Avec "uniquement les VODs", la liste est limitée aux
+    /// rediffusions (sans temps forts ni uploads).
+    fn listing_url(&self, channel: &Channel) -> String {
+        let videos = format!("{}/videos", channel.url.trim_end_matches('/'));
+        if channel.rules.only_vods {
+            format!("{videos}?filter=archives")
+        } else {
+            videos
+        }
+    }
+
+    /// Les listes à plat donnent l'ID numérique, sans le "v" des VODs
+    fn normalize_video(&self, mut video: VideoMetadata) -> VideoMetadata {
+        if video.ie_key.as_deref() == Some("TwitchVod")
+            && video.id.chars().all(|c| c.is_ascii_digit())
+        {
+            video.id = format!("v{}", video.id);
+        }
+        video
+    }
+
+    fn download_args(&self, format: FormatProfile) -> Vec<String> {
+        muxed_stream_args(format)
+    }
+}
+
+/// Chemins Kick de premier niveau qui ne sont pas des chaînes
+const KICK_RESERVED: &[&str] = &[
+    "browse",
+    "categories",
+    "category",
+    "clips",
+    "dashboard",
+    "following",
+    "search",
+    "settings",
+    "subscriptions",
+    "video",
+];
+
+impl Extractor for Kick {
+    fn platform(&self) -> Platform {
+        Platform::Kick
+    }
+
+    fn label(&self) -> &'static str {
+        "Kick"
+    }
+
+    fn handles_host(&self, host: &str) -> bool {
+        host == "kick.com"
+    }
+
+    fn classify(&self, url: &UrlParts) -> Option<UrlKind> {
+        let kind = match url.segments.as_slice() {
+            ["video", id] | [_, "videos", id] => UrlKind::Video(id.to_string()),
+            [_, "clips", id] => UrlKind::Clip(id.to_string()),
+            [name, ..] if !KICK_RESERVED.contains(&name.to_ascii_lowercase().as_str()) => {
+                match url.param("clip") {
+                    Some(clip) => UrlKind::Clip(clip),
+                    None => UrlKind::Channel(name.to_string()),
+                }
+            }
+            _ => return None,
+        };
+        Some(kind)
+    }
+
+    fn channel_url(&self, kind: &UrlKind, _url: &UrlParts) -> Option<String> {
+        match kind {
+            UrlKind::Channel(name) => Some(format!("https://kick.com/{name}")),
+            _ => None,
+        }
+    }
+
+    fn channel_id(&self, kind: &UrlKind, _url: &UrlParts) -> Option<String> {
+        match kind {
+            UrlKind::Channel(name) => Some(name.to_ascii_lowercase()),
+            _ => None,
+        }
+    }
+
+    /// Comme sur Twitch, les rediffusions sont sous `/videos`
+    fn listing_url(&self, channel: &Channel) -> String {
+        format!("{}/videos", channel.url.trim_end_matches('/'))
+    }
+
+    fn download_args(&self, format: FormatProfile) -> Vec<String> {
+        muxed_stream_args(format)
+    }
+}
+
+/// Chemins Vimeo de premier niveau qui ne sont pas des comptes
+const VIMEO_RESERVED: &[&str] = &[
+    "album",
+    "categories",
+    "channels",
+    "features",
+    "groups",
+    "help",
+    "join",
+    "log_in",
+    "ondemand",
+    "search",
+    "settings",
+    "showcase",
+    "upload",
+    "watch",
+];
+
+impl Extractor for Vimeo {
+    fn platform(&self) -> Platform {
+        Platform::Vimeo
+    }
+
+    fn label(&self) -> &'static str {
+        "Vimeo"
+    }
+
+    fn download_args(&self, format: FormatProfile) -> Vec<String> {
+        any_container_args(format)
+    }
+
+    fn handles_host(&self, host: &str) -> bool {
+        matches!(host, "vimeo.com" | "player.vimeo.com")
+    }
+
+    fn classify(&self, url: &UrlParts) -> Option<UrlKind> {
+        if url.host == "player.vimeo.com" {
+            return match url.segments.as_slice() {
+                ["video", id] => Some(UrlKind::Video(id.to_string())),
+                _ => None,
+            };
+        }
+
+        let kind = match url.segments.as_slice() {
+            [id, ..] if is_numeric(id) => UrlKind::Video(id.to_string()),
+            ["channels", _, id, ..] | ["groups", _, "videos", id] if is_numeric(id) => {
+                UrlKind::Video(id.to_string())
+            }
+            ["channels" | "groups", name, ..] => UrlKind::Channel(name.to_string()),
+            ["showcase" | "album", id, ..] => UrlKind::Playlist(id.to_string()),
+            [name, ..] if !VIMEO_RESERVED.contains(&name.to_ascii_lowercase().as_str()) => {
+                UrlKind::Channel(name.to_string())
+            }
+            _ => return None,
+        };
+        Some(kind)
+    }
+
+    /// Compte (`vimeo.com/nom`), chaîne ou groupe
+    fn channel_url(&self, kind: &UrlKind, url: &UrlParts) -> Option<String> {
+        match (kind, url.segments[0]) {
+            (UrlKind::Channel(name), section @ ("channels" | "groups")) => {
+                Some(format!("https://vimeo.com/{section}/{name}"))
+            }
+            (UrlKind::Channel(name), _) => Some(format!("https://vimeo.com/{name}")),
+            _ => None,
+        }
+    }
+
+    fn channel_id(&self, kind: &UrlKind, url: &UrlParts) -> Option<String> {
+        match (kind, url.segments[0]) {
+            (UrlKind::Channel(name), section @ ("channels" | "groups")) => {
+                Some(format!("{section}/{}", name.to_ascii_lowercase()))
+            }
+            (UrlKind::Channel(name), _) => Some(name.to_ascii_lowercase()),
+            _ => None,
+        }
+    }
+
+    /// Les vidéos d'un compte sont listées sous `/videos`
+    fn listing_url(&self, channel: &Channel) -> String {
+        if channel.url.contains("/channels/") || channel.url.contains("/groups/") {
+            channel.url.clone()
+        } else {
+            format!("{}/videos", channel.url.trim_end_matches('/'))
+        }
+    }
+}
+
+/// Nom d'un compte ou d'une chaîne PeerTube, qui peut venir d'une autre
+/// instance (`nom@instance`)
+fn peertube_name(handle: &str) -> Option<String> {
+    match handle.split_once('@') {
+        Some((name, host)) if is_identifier(host) => Some(name.to_string()),
+        Some(_) => None,
+        None => Some(handle.to_string()),
+    }
+}
+
+impl Extractor for PeerTube {
+    fn platform(&self) -> Platform {
+        Platform::PeerTube
+    }
+
+    fn label(&self) -> &'static str {
+        "PeerTube"
+    }
+
+    fn download_args(&self, format: FormatProfile) -> Vec<String> {
+        any_container_args(format)
+    }
+
+    fn handles_host(&self, host: &str) -> bool {
+        PEERTUBE_INSTANCES.contains(&host)
+    }
+
+    fn classify(&self, url: &UrlParts) -> Option<UrlKind> {
+        let kind = match url.segments.as_slice() {
+            ["w", "p", id] | ["videos", "watch", "playlist", id] => {
+                UrlKind::Playlist(id.to_string())
+            }
+            ["w", id] | ["videos", "watch" | "embed", id] => UrlKind::Video(id.to_string()),
+            ["c" | "video-channels" | "a" | "accounts", handle, ..] => {
+                UrlKind::Channel(peertube_name(handle)?)
+            }
+            _ => return None,
+        };
+        Some(kind)
+    }
+
+    /// Chaîne (`/c/`) ou compte (`/a/`) sur l'instance du lien
+    fn channel_url(&self, kind: &UrlKind, url: &UrlParts) -> Option<String> {
+        let UrlKind::Channel(_) = kind else {
+            return None;
+        };
+        let section = match url.segments[0] {
+            "c" | "video-channels" => "c",
+            _ => "a",
+        };
+        Some(format!(
+            "https://{}/{section}/{}",
+            url.host, url.segments[1]
+        ))
+    }
+
+    /// Handle complet `nom@instance`
+    fn channel_id(&self, kind: &UrlKind, url: &UrlParts) -> Option<String> {
+        let UrlKind::Channel(_) = kind else {
+            return None;
+        };
+        let handle = url.segments[1].to_ascii_lowercase();
+        if handle.contains('@') {
+            Some(handle)
+        } else {
+            Some(format!("{handle}@{}", url.host))
+        }
+    }
+}
+
+/// Chemins Dailymotion de premier niveau qui ne sont pas des comptes
+const DAILYMOTION_RESERVED: &[&str] = &[
+    "embed",
+    "following",
+    "library",
+    "live",
+    "partner",
+    "playlist",
+    "search",
+    "settings",
+    "signin",
+    "signup",
+    "upload",
+    "video",
+];
+
+impl Extractor for Dailymotion {
+    fn platform(&self) -> Platform {
+        Platform::Dailymotion
+    }
+
+    fn label(&self) -> &'static str {
+        "Dailymotion"
+    }
+
+    fn download_args(&self, format: FormatProfile) -> Vec<String> {
+        any_container_args(format)
+    }
+
+    fn handles_host(&self, host: &str) -> bool {
+        matches!(host, "dailymotion.com" | "dai.ly" | "geo.dailymotion.com")
+    }
+
+    fn classify(&self, url: &UrlParts) -> Option<UrlKind> {
+        // Les anciens liens ajoutent le titre à l'identifiant: "x7tgad0_titre"
+        let video = |id: &str| UrlKind::Video(id.split('_').next().unwrap_or_default().to_string());
+        let kind = match (url.host.as_str(), url.segments.as_slice()) {
+            ("dai.ly", [id]) => video(id),
+            ("dai.ly", _) => return None,
+            ("geo.dailymotion.com", _) => video(&url.param("video")?),
+            (_, ["video", id] | ["embed", "video", id]) => video(id),
+            (_, ["playlist", id]) => UrlKind::Playlist(id.to_string()),
+            (_, [name, ..])
+                if !DAILYMOTION_RESERVED.contains(&name.to_ascii_lowercase().as_str()) =>
+            {
+                UrlKind::Channel(name.to_string())
+            }
+            _ => return None,
+        };
+        Some(kind)
+    }
+
+    fn channel_url(&self, kind: &UrlKind, _url: &UrlParts) -> Option<String> {
+        match kind {
+            UrlKind::Channel(name) => Some(format!("https://www.dailymotion.com/{name}")),
+            _ => None,
+        }
+    }
+
+    fn channel_id(&self, kind: &UrlKind, _url: &UrlParts) -> Option<String> {
+        match kind {
+            UrlKind::Channel(name) => Some(name.to_ascii_lowercase()),
+            _ => None,
+        }
+    }
+}
+
+/// Nom d'une chaîne ou d'une vidéo Odysee, sans son identifiant de claim
+/// (`nom:1f`)
+fn odysee_name(segment: &str) -> Option<String> {
+    let (name, claim) = segment.split_once(':').unwrap_or((segment, ""));
+    claim
+        .chars()
+        .all(|c| c.is_ascii_hexdigit())
+        .then(|| name.to_string())
+}
+
+impl Extractor for Odysee {
+    fn platform(&self) -> Platform {
+        Platform::Odysee
+    }
+
+    fn label(&self) -> &'static str {
+        "Odysee"
+    }
+
+    fn download_args(&self, format: FormatProfile) -> Vec<String> {
+        any_container_args(format)
+    }
+
+    fn handles_host(&self, host: &str) -> bool {
+        host == "odysee.com"
+    }
+
+    fn classify(&self, url: &UrlParts) -> Option<UrlKind> {
+        let kind = match url.segments.as_slice() {
+            ["$", "embed", video, ..] => UrlKind::Video(odysee_name(video)?),
+            [channel, video] if channel.starts_with('@') => UrlKind::Video(odysee_name(video)?),
+            [channel] if channel.starts_with('@') => UrlKind::Handle(odysee_name(&channel[1..])?),
+            [video] => UrlKind::Video(odysee_name(video)?),
+            _ => return None,
+        };
+        Some(kind)
+    }
+
+    /// Le claim est gardé: plusieurs chaînes peuvent porter le même nom
+    fn channel_url(&self, kind: &UrlKind, url: &UrlParts) -> Option<String> {
+        match kind {
+            UrlKind::Handle(_) => Some(format!("https://odysee.com/{}", url.segments[0])),
+            _ => None,
+        }
+    }
+
+    fn channel_id(&self, kind: &UrlKind, url: &UrlParts) -> Option<String> {
+        match kind {
+            UrlKind::Handle(_) => Some(url.segments[0][1..].to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// Onglets à ignorer pour nommer une chaîne d'un site inconnu
+const GENERIC_TABS: &[&str] = &[
+    "c",
+    "channel",
+    "channels",
+    "live",
+    "playlists",
+    "profile",
+    "streams",
+    "u",
+    "user",
+    "videos",
+];
+
+impl Extractor for Generic {
+    fn platform(&self) -> Platform {
+        Platform::Other
+    }
+
+    fn label(&self) -> &'static str {
+        "Autre"
+    }
+
+    /// Toute page d'un site: c'est yt-dlp qui dira s'il sait la lister. La
+    /// chaîne est nommée d'après le dernier segment utile du chemin, ou l'hôte.
+    fn classify(&self, url: &UrlParts) -> Option<UrlKind> {
+        if !url.host.contains('.') || !is_identifier(&url.host) {
+            return None;
+        }
+        let name = url
+            .segments
+            .iter()
+            .rev()
+            .map(|segment| segment.trim_start_matches('@'))
+            .find(|segment| is_identifier(segment) && !GENERIC_TABS.contains(segment))
+            .unwrap_or(&url.host);
+        Some(UrlKind::Channel(name.to_string()))
+    }
+
+    /// Lien tel que donné, paramètres compris (sans fragment)
+    fn channel_url(&self, _kind: &UrlKind, url: &UrlParts) -> Option<String> {
+        let mut channel_url = format!("https://{}", url.host);
+        for segment in &url.segments {
+            channel_url.push('/');
+            channel_url.push_str(segment);
+        }
+        if !url.query.is_empty() {
+            channel_url.push('?');
+            channel_url.push_str(url.query);
+        }
+        Some(channel_url)
+    }
+
+    /// Sans identifiant connu, l'URL de la chaîne en tient lieu
+    fn channel_id(&self, kind: &UrlKind, url: &UrlParts) -> Option<String> {
+        self.channel_url(kind, url)
+    }
+
+    fn download_args(&self, format: FormatProfile) -> Vec<String> {
+        any_container_args(format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extractor_lookup() {
+        assert_eq!(for_host("youtu.be").unwrap().platform(), Platform::YouTube);
+        assert_eq!(
+            for_host("clips.twitch.tv").unwrap().platform(),
+            Platform::Twitch
+        );
+        assert!(for_host("example.com").is_none());
+        assert_eq!(
+            for_host("framatube.org").unwrap().platform(),
+            Platform::PeerTube
+        );
+        // Chemins de type PeerTube sur un hôte inconnu
+        assert!(for_url(&UrlParts::split("https://blog.example.org/c/actualites")).is_none());
+        for platform in Platform::ALL {
+            assert_eq!(platform.extractor().platform(), platform);
+        }
+    }
+
+    #[test]
+    fn test_twitch_listing_and_videos() {
+        let mut channel = Channel::from_url("https://www.twitch.tv/chaine/").unwrap();
+        assert_eq!(
+            Twitch.listing_url(&channel),
+            "https://www.twitch.tv/chaine/videos"
+        );
+        channel.rules.only_vods = true;
+        assert_eq!(
+            Twitch.listing_url(&channel),
+            "https://www.twitch.tv/chaine/videos?filter=archives"
+        );
+
+        let vod: VideoMetadata = serde_json::from_value(serde_json::json!({
+            "ie_key": "TwitchVod",
+            "id": "2000000",
+            "title": "VOD",
+            "url": "https://www.twitch.tv/videos/2000000",
+        }))
+        .unwrap();
+        assert_eq!(Twitch.normalize_video(vod).id, "v2000000");
+    }
+
+    #[test]
+    fn test_listing_urls() {
+        let listing = |url: &str| {
+            let channel = Channel::parse_or_generic(url).unwrap();
+            channel.platform.extractor().listing_url(&channel)
+        };
+        assert_eq!(
+            listing("https://www.youtube.com/@Chaine/shorts"),
+            "https://www.youtube.com/@Chaine/videos"
+        );
+        assert_eq!(
+            listing("https://www.youtube.com/channel/UCabcdefghijklmnopqrstuv"),
+            "https://www.youtube.com/channel/UCabcdefghijklmnopqrstuv/videos"
+        );
+        assert_eq!(
+            listing("https://kick.com/streamer"),
+            "https://kick.com/streamer/videos"
+        );
+        assert_eq!(
+            listing("https://vimeo.com/createur"),
+            "https://vimeo.com/createur/videos"
+        );
+        assert_eq!(
+            listing("https://vimeo.com/channels/staffpicks/videos"),
+            "https://vimeo.com/channels/staffpicks"
+        );
+        assert_eq!(
+            listing("https://video.blender.org/video-channels/chaine"),
+            "https://video.blender.org/c/chaine"
+        );
+        assert_eq!(
+            listing("https://media.example.com/createur"),
+            "https://media.example.com/createur"
+        );
+    }
+
+    #[test]
+    fn test_download_args() {
+        assert_eq!(
+            download_args("https://www.twitch.tv/videos/1", FormatProfile::Max720p),
+            ["-f", "best[height<=720]/best"]
+        );
+        assert_eq!(
+            download_args("https://kick.com/video/abc", FormatProfile::Best),
+            ["-f", "best"]
+        );
+        assert_eq!(
+            download_args("https://media.example.com/v/1", FormatProfile::Max480p),
+            [
+                "-f",
+                "bestvideo*[height<=480]+bestaudio/best[height<=480]",
+                "--merge-output-format",
+                "mp4"
+            ]
+        );
+        for url in [
+            "https://vimeo.com/123456789",
+            "https://framatube.org/w/9c9de5e8-0a1e-484a-b099-e80766180a6d",
+            "https://www.dailymotion.com/video/x7tgad0",
+            "https://odysee.com/@chaine:7a/une-video:3",
+        ] {
+            assert_eq!(
+                download_args(url, FormatProfile::Best),
+                [
+                    "-f",
+                    "bestvideo*+bestaudio/best",
+                    "--merge-output-format",
+                    "mp4"
+                ],
+                "{url}"
+            );
+        }
+        assert_eq!(
+            download_args("https://youtu.be/abc", FormatProfile::AudioOnly),
+            [
+                "-f",
+                "bestaudio[ext=m4a]/bestaudio",
+                "-x",
+                "--audio-format",
+                "m4a"
+            ]
+        );
+        assert_eq!(
+            download_args("https://www.twitch.tv/videos/1", FormatProfile::AudioOnly),
+            ["-f", "audio_only/bestaudio", "-x", "--audio-format", "m4a"]
+        );
+    }
+}
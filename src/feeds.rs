@@ -0,0 +1,272 @@
+use crate::scanner::VideoMetadata;
+use anyhow::{Context, Result};
+use chrono::DateTime;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+const YOUTUBE_URL: &str = "https://www.youtube.com";
+const FEED_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Runtime tokio réservé à reqwest, le reste de l'application tournant sur smol
+fn runtime() -> &'static tokio::runtime::Runtime {
+    static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("ndownloader-http")
+            .enable_all()
+            .build()
+            .expect("Impossible de démarrer le runtime HTTP")
+    })
+}
+
+/// Flux Atom des dernières vidéos d'une chaîne YouTube (une quinzaine au plus),
+/// bien plus léger qu'un scan yt-dlp. Les durées n'y figurent pas.
+#[derive(Clone)]
+pub struct FeedClient {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl Default for FeedClient {
+    fn default() -> Self {
+        Self::with_base_url(YOUTUBE_URL)
+    }
+}
+
+impl FeedClient {
+    pub fn with_base_url(base_url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub fn feed_url(&self, channel_id: &str) -> String {
+        format!("{}/feeds/videos.xml?channel_id={channel_id}", self.base_url)
+    }
+
+    /// Vidéos du flux, de la plus récente à la plus ancienne
+    pub async fn fetch(&self, channel_id: &str) -> Result<Vec<VideoMetadata>> {
+        let client = self.client.clone();
+        let url = self.feed_url(channel_id);
+        let content = runtime()
+            .spawn(async move {
+                let response = client
+                    .get(&url)
+                    .timeout(FEED_TIMEOUT)
+                    .send()
+                    .await?
+                    .error_for_status()?;
+                anyhow::Ok(response.text().await?)
+            })
+            .await
+            .context("Requête du flux interrompue")??;
+
+        parse_feed(&content)
+    }
+}
+
+/// Entrée du flux en cours de lecture
+#[derive(Default)]
+struct FeedEntry {
+    id: Option<String>,
+    title: Option<String>,
+    url: Option<String>,
+    published: Option<String>,
+    author: Option<String>,
+    thumbnail: Option<String>,
+}
+
+impl FeedEntry {
+    fn into_video(self) -> Option<VideoMetadata> {
+        let id = self.id?;
+        let url = self
+            .url
+            .unwrap_or_else(|| format!("{YOUTUBE_URL}/watch?v={id}"));
+        let upload_date = self
+            .published
+            .and_then(|date| DateTime::parse_from_rfc3339(&date).ok())
+            .map(|date| date.format("%Y%m%d").to_string());
+
+        Some(VideoMetadata {
+            title: self.title.unwrap_or_else(|| id.clone()),
+            media_type: url.contains("/shorts/").then(|| "short".to_string()),
+            video_type: None,
+            id,
+            url,
+            duration: None,
+            upload_date,
+            uploader: self.author,
+            extractor_key: Some("Youtube".to_string()),
+            ie_key: None,
+            filesize_approx: None,
+            live_status: None,
+            thumbnail: self.thumbnail,
+            chapters: Vec::new(),
+            formats: Vec::new(),
+            enriched: false,
+        })
+    }
+}
+
+/// Vidéos du flux plus récentes que `latest_id`, ou `None` si cette vidéo n'y
+/// figure plus et que des vidéos plus anciennes ont pu être manquées
+pub fn videos_since(feed: Vec<VideoMetadata>, latest_id: &str) -> Option<Vec<VideoMetadata>> {
+    let position = feed.iter().position(|video| video.id == latest_id)?;
+    Some(feed.into_iter().take(position).collect())
+}
+
+fn attribute(element: &BytesStart, name: &str) -> Result<Option<String>> {
+    Ok(match element.try_get_attribute(name)? {
+        Some(attribute) => Some(attribute.unescape_value()?.into_owned()),
+        None => None,
+    })
+}
+
+/// Lit un flux Atom de chaîne YouTube
+pub fn parse_feed(content: &str) -> Result<Vec<VideoMetadata>> {
+    let mut reader = Reader::from_str(content);
+    reader.config_mut().trim_text(true);
+    let mut videos = Vec::new();
+    let mut entry: Option<FeedEntry> = None;
+    // Élément dont le texte est en cours de lecture
+    let mut current: Vec<u8> = Vec::new();
+
+    loop {
+        let event = reader.read_event().context("Flux Atom invalide")?;
+        match &event {
+            Event::Start(element) | Event::Empty(element) => {
+                let name = element.name().as_ref().to_vec();
+                match (name.as_slice(), entry.as_mut()) {
+                    (b"entry", _) => entry = Some(FeedEntry::default()),
+                    (b"link", Some(entry))
+                        if attribute(element, "rel")?.as_deref() == Some("alternate") =>
+                    {
+                        entry.url = attribute(element, "href")?;
+                    }
+                    (b"media:thumbnail", Some(entry)) => {
+                        entry.thumbnail = attribute(element, "url")?;
+                    }
+                    _ => {}
+                }
+                if matches!(event, Event::Start(_)) {
+                    current = name;
+                }
+            }
+            Event::Text(text) => {
+                let Some(entry) = entry.as_mut() else {
+                    continue;
+                };
+                let text = text.unescape()?.into_owned();
+                match current.as_slice() {
+                    b"yt:videoId" => entry.id = Some(text),
+                    b"title" => entry.title = Some(text),
+                    b"published" => entry.published = Some(text),
+                    b"name" => entry.author = Some(text),
+                    _ => {}
+                }
+            }
+            Event::End(element) => {
+                if element.name().as_ref() == b"entry" {
+                    videos.extend(entry.take().and_then(FeedEntry::into_video));
+                }
+                current.clear();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(videos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    const FEED: &str = include_str!("../tests/fixtures/youtube_channel_feed.xml");
+
+    /// Sert une seule réponse HTTP en local et renvoie l'URL de base et la
+    /// ligne de requête reçue
+    fn serve(status: &'static str, body: &'static str) -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 4096];
+            let read = stream.read(&mut request).unwrap();
+            let request = String::from_utf8_lossy(&request[..read]);
+            sender
+                .send(request.lines().next().unwrap_or_default().to_string())
+                .ok();
+            let response = format!(
+                "HTTP/1.1 {status}\r\nContent-Type: application/atom+xml; charset=UTF-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+        (format!("http://{address}"), receiver)
+    }
+
+    #[test]
+    fn test_parse_feed_fixture() {
+        let videos = parse_feed(FEED).unwrap();
+        let ids: Vec<&str> = videos.iter().map(|video| video.id.as_str()).collect();
+        assert_eq!(ids, ["vid3333333", "short222222", "vid1111111"]);
+
+        let newest = &videos[0];
+        assert_eq!(
+            newest.title,
+            "Troisième vidéo : les \"coulisses\" & le reste"
+        );
+        assert_eq!(newest.url, "https://www.youtube.com/watch?v=vid3333333");
+        assert_eq!(newest.upload_date.as_deref(), Some("20240612"));
+        assert_eq!(newest.uploader.as_deref(), Some("Chaîne de test"));
+        assert_eq!(
+            newest.thumbnail.as_deref(),
+            Some("https://i1.ytimg.com/vi/vid3333333/hqdefault.jpg")
+        );
+        assert_eq!(newest.platform(), "youtube");
+        assert!(newest.duration.is_none());
+        assert!(!newest.enriched);
+
+        assert_eq!(videos[1].media_type.as_deref(), Some("short"));
+        assert!(videos[2].media_type.is_none());
+    }
+
+    #[test]
+    fn test_videos_since_latest_id() {
+        let feed = parse_feed(FEED).unwrap();
+        let new_videos = videos_since(feed.clone(), "vid1111111").unwrap();
+        assert_eq!(new_videos.len(), 2);
+        assert!(videos_since(feed.clone(), "vid3333333").unwrap().is_empty());
+        assert!(videos_since(feed, "plus_dans_le_flux").is_none());
+    }
+
+    #[test]
+    fn test_fetch_feed_from_local_server() {
+        let (base_url, requests) = serve("200 OK", FEED);
+        let client = FeedClient::with_base_url(base_url);
+
+        let videos = smol::block_on(client.fetch("UCabcdefghijklmnopqrstuv")).unwrap();
+        assert_eq!(videos.len(), 3);
+        assert_eq!(
+            requests.recv().unwrap(),
+            "GET /feeds/videos.xml?channel_id=UCabcdefghijklmnopqrstuv HTTP/1.1"
+        );
+    }
+
+    #[test]
+    fn test_fetch_feed_reports_http_errors() {
+        let (base_url, _requests) = serve("404 Not Found", "");
+        let client = FeedClient::with_base_url(base_url);
+        assert!(smol::block_on(client.fetch("UCinconnu")).is_err());
+    }
+}
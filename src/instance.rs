@@ -0,0 +1,73 @@
+use crate::control;
+use anyhow::{Context, Result};
+use std::fs::{File, OpenOptions};
+use std::path::PathBuf;
+
+/// Préfixe des liens ouverts via le gestionnaire d'URL du fichier .desktop
+const URL_SCHEME: &str = "ndownloader:";
+
+/// Verrou garantissant une seule instance de l'application par utilisateur.
+/// Libéré à la fin du processus.
+pub struct InstanceLock {
+    _file: File,
+}
+
+impl InstanceLock {
+    /// Prend le verrou, ou retourne `None` si une autre instance le détient
+    pub fn acquire() -> Result<Option<Self>> {
+        let path = lock_path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("Impossible d'ouvrir {}", path.display()))?;
+
+        match fs4::FileExt::try_lock(&file) {
+            Ok(()) => Ok(Some(Self { _file: file })),
+            Err(fs4::TryLockError::WouldBlock) => Ok(None),
+            Err(fs4::TryLockError::Error(error)) => Err(error.into()),
+        }
+    }
+}
+
+fn lock_path() -> PathBuf {
+    control::socket_path().with_extension("lock")
+}
+
+/// URL passée en argument, sans le préfixe du gestionnaire "ndownloader:"
+pub fn handoff_url(argument: &str) -> String {
+    match argument.strip_prefix(URL_SCHEME) {
+        Some(url) => url.trim_start_matches('/').to_string(),
+        None => argument.to_string(),
+    }
+}
+
+/// Transmet une URL à l'instance en cours (ajout de chaîne ou téléchargement)
+pub fn forward(url: &str) -> Result<()> {
+    let result = control::call("open", serde_json::json!({ "url": url }))?
+        .context("L'instance en cours ne répond pas sur son socket de contrôle")?;
+    println!("{}", serde_json::to_string_pretty(&result)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handoff_url() {
+        assert_eq!(
+            handoff_url("ndownloader://https://www.twitch.tv/chaine"),
+            "https://www.twitch.tv/chaine"
+        );
+        assert_eq!(
+            handoff_url("ndownloader:https://youtu.be/abc"),
+            "https://youtu.be/abc"
+        );
+        assert_eq!(handoff_url("https://youtu.be/abc"), "https://youtu.be/abc");
+    }
+}
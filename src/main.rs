@@ -1,18 +1,73 @@
 use anyhow::Result;
+use clap::Parser;
 use gpui::*;
 
+mod archive;
 mod cache;
+mod channels;
+mod cli;
+mod config;
+mod control;
+mod daemon;
+mod download_error;
+mod download_progress;
 mod downloader_queue;
+mod extractors;
+mod feeds;
+mod instance;
+mod media_url;
+mod monitor;
 mod notifications;
+mod paths;
+mod rules;
 mod scanner;
+mod storage;
+mod subscriptions;
 mod ui;
 
+use cli::Cli;
+use config::Config;
+use instance::InstanceLock;
+use notifications::Notification;
 use ui::{actions::*, NDownloaderApp};
 
 fn main() -> Result<()> {
-    tracing_subscriber::fmt()
-        .with_max_level(tracing::Level::WARN)
-        .init();
+    let cli = Cli::parse();
+
+    if cli.headless {
+        let log_file = cli.log_file.unwrap_or_else(daemon::default_log_file);
+        daemon::init_logging(&log_file)?;
+    } else {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::WARN)
+            .init();
+    }
+
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(error) => {
+            tracing::error!("Configuration invalide: {}", error);
+            Notification::error("Configuration invalide", &error.to_string());
+            anyhow::bail!("{error}");
+        }
+    };
+
+    if let Some(command) = cli.command {
+        return cli::run(command, config, cli.json);
+    }
+
+    let url = cli.url.as_deref().map(instance::handoff_url);
+    let Some(_lock) = InstanceLock::acquire()? else {
+        // Une instance tourne déjà: lui confier le lien
+        let Some(url) = url else {
+            anyhow::bail!("NDownloader est déjà lancé");
+        };
+        return instance::forward(&url);
+    };
+
+    if cli.headless {
+        return daemon::run(config, url);
+    }
 
     Application::new().run(|cx: &mut App| {
         cx.activate(true);
@@ -49,8 +104,12 @@ fn main() -> Result<()> {
                 }),
                 ..Default::default()
             },
-            |window, cx| cx.new(|cx| NDownloaderApp::new(window, cx)),
+            |window, cx| cx.new(|cx| NDownloaderApp::new(config, window, cx)),
         );
+
+        if let Some(url) = url {
+            control::open_in_background(cx, url);
+        }
     });
 
     Ok(())
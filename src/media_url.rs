@@ -0,0 +1,382 @@
+use crate::channels::Platform;
+use crate::extractors::{self, Extractor, Generic};
+use thiserror::Error;
+
+/// Ce que désigne un lien de vidéo ou de chaîne
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrlKind {
+    /// Chaîne par identifiant (`UC...` sur YouTube) ou par nom
+    Channel(String),
+    /// Chaîne par handle (`@nom` sur YouTube et Odysee), sans le `@`
+    Handle(String),
+    /// Ancienne URL YouTube `/user/nom` ou `/c/nom`
+    LegacyUser(String),
+    Playlist(String),
+    Video(String),
+    TwitchVod(String),
+    Clip(String),
+    /// Direct d'une chaîne, par nom de chaîne
+    Live(String),
+}
+
+impl UrlKind {
+    fn label(&self) -> &'static str {
+        match self {
+            UrlKind::Channel(_) | UrlKind::Handle(_) | UrlKind::LegacyUser(_) => "une chaîne",
+            UrlKind::Playlist(_) => "une playlist",
+            UrlKind::Video(_) => "une vidéo",
+            UrlKind::TwitchVod(_) => "une VOD Twitch",
+            UrlKind::Clip(_) => "un clip",
+            UrlKind::Live(_) => "un direct",
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum UrlError {
+    #[error("Lien vide")]
+    Empty,
+    #[error("Site non pris en charge: {0}")]
+    UnsupportedSite(String),
+    #[error("Lien non reconnu: {0}")]
+    Unrecognized(String),
+    #[error("Ce lien désigne {0}, pas une chaîne")]
+    NotAChannel(&'static str),
+}
+
+/// Lien classé, avec l'URL canonique de la chaîne quand il en désigne une
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaUrl {
+    pub platform: Platform,
+    pub kind: UrlKind,
+    pub channel_url: Option<String>,
+    /// Identifiant stable de la chaîne quand le lien le contient déjà
+    pub channel_id: Option<String>,
+}
+
+/// Lien découpé en hôte, segments de chemin et paramètres
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlParts<'a> {
+    /// Hôte en minuscules, sans "www." ni port
+    pub host: String,
+    pub segments: Vec<&'a str>,
+    pub query: &'a str,
+}
+
+impl<'a> UrlParts<'a> {
+    pub fn split(input: &'a str) -> Self {
+        let rest = input
+            .strip_prefix("https://")
+            .or_else(|| input.strip_prefix("http://"))
+            .unwrap_or(input);
+        let rest = rest.split('#').next().unwrap_or_default();
+        let (rest, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+        let host = host
+            .split(':')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        Self {
+            host: host.strip_prefix("www.").unwrap_or(&host).to_string(),
+            segments: path.split('/').filter(|s| !s.is_empty()).collect(),
+            query,
+        }
+    }
+
+    /// Valeur d'un paramètre de requête, si c'est un identifiant plausible
+    pub fn param(&self, name: &str) -> Option<String> {
+        self.query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, value)| *key == name && is_identifier(value))
+            .map(|(_, value)| value.to_string())
+    }
+}
+
+impl MediaUrl {
+    pub fn parse(input: &str) -> Result<Self, UrlError> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(UrlError::Empty);
+        }
+        let url = UrlParts::split(input);
+        if url.host.is_empty() {
+            return Err(UrlError::Unrecognized(input.to_string()));
+        }
+
+        let extractor =
+            extractors::for_url(&url).ok_or_else(|| UrlError::UnsupportedSite(url.host.clone()))?;
+        Self::classify(extractor, &url).ok_or_else(|| UrlError::Unrecognized(input.to_string()))
+    }
+
+    /// Comme `parse`, mais un lien d'un site inconnu est confié à yt-dlp
+    /// (plateforme "autre")
+    pub fn parse_or_generic(input: &str) -> Result<Self, UrlError> {
+        match Self::parse(input) {
+            Err(UrlError::UnsupportedSite(host)) => {
+                let url = UrlParts::split(input.trim());
+                Self::classify(&Generic, &url).ok_or(UrlError::UnsupportedSite(host))
+            }
+            result => result,
+        }
+    }
+
+    fn classify(extractor: &dyn Extractor, url: &UrlParts) -> Option<Self> {
+        let kind = extractor.classify(url).filter(kind_is_valid)?;
+        Some(Self {
+            platform: extractor.platform(),
+            channel_url: extractor.channel_url(&kind, url),
+            channel_id: extractor.channel_id(&kind, url),
+            kind,
+        })
+    }
+
+    /// Nom de la chaîne désignée par le lien (dossier de téléchargement)
+    pub fn channel_name(&self) -> Option<&str> {
+        match &self.kind {
+            UrlKind::Channel(name)
+            | UrlKind::Handle(name)
+            | UrlKind::LegacyUser(name)
+            | UrlKind::Live(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Erreur à afficher quand le lien devait désigner une chaîne
+    pub fn require_channel(&self) -> Result<(), UrlError> {
+        match self.channel_url {
+            Some(_) => Ok(()),
+            None => Err(UrlError::NotAChannel(self.kind.label())),
+        }
+    }
+}
+
+fn kind_is_valid(kind: &UrlKind) -> bool {
+    let value = match kind {
+        UrlKind::Channel(value)
+        | UrlKind::Handle(value)
+        | UrlKind::LegacyUser(value)
+        | UrlKind::Playlist(value)
+        | UrlKind::Video(value)
+        | UrlKind::TwitchVod(value)
+        | UrlKind::Clip(value)
+        | UrlKind::Live(value) => value,
+    };
+    is_identifier(value)
+}
+
+/// Identifiant, handle ou slug plausible (les handles peuvent être encodés en %XX)
+pub fn is_identifier(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '%'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind(url: &str) -> UrlKind {
+        MediaUrl::parse(url).unwrap().kind
+    }
+
+    #[test]
+    fn test_youtube_links() {
+        let id = "UCabcdefghijklmnopqrstuv".to_string();
+        assert_eq!(
+            kind("https://www.youtube.com/watch?v=dQw4w9WgXcQ&list=PL1"),
+            UrlKind::Video("dQw4w9WgXcQ".to_string())
+        );
+        assert_eq!(
+            kind("youtu.be/dQw4w9WgXcQ?si=x"),
+            UrlKind::Video("dQw4w9WgXcQ".to_string())
+        );
+        assert_eq!(
+            kind("https://m.youtube.com/shorts/abc_123"),
+            UrlKind::Video("abc_123".to_string())
+        );
+        assert_eq!(
+            kind("https://youtube.com/playlist?list=PLxyz"),
+            UrlKind::Playlist("PLxyz".to_string())
+        );
+        assert_eq!(
+            kind("https://www.youtube.com/user/Ancien/videos"),
+            UrlKind::LegacyUser("Ancien".to_string())
+        );
+        assert_eq!(
+            kind(&format!("https://www.youtube.com/channel/{id}/live")),
+            UrlKind::Live(id.clone())
+        );
+
+        let parsed = MediaUrl::parse("https://m.youtube.com/@Chaine/videos?si=abc").unwrap();
+        assert_eq!(parsed.kind, UrlKind::Handle("Chaine".to_string()));
+        assert_eq!(parsed.channel_name(), Some("Chaine"));
+        assert_eq!(
+            parsed.channel_url.as_deref(),
+            Some("https://www.youtube.com/@Chaine")
+        );
+
+        let parsed = MediaUrl::parse(&format!("http://youtube.com/channel/{id}")).unwrap();
+        assert_eq!(parsed.channel_id, Some(id));
+    }
+
+    #[test]
+    fn test_twitch_links() {
+        assert_eq!(
+            kind("https://www.twitch.tv/videos/v123456"),
+            UrlKind::TwitchVod("123456".to_string())
+        );
+        assert_eq!(
+            kind("https://clips.twitch.tv/DrolePetitClip-abc"),
+            UrlKind::Clip("DrolePetitClip-abc".to_string())
+        );
+        assert_eq!(
+            kind("https://www.twitch.tv/chaine/clip/Slug-1?filter=clips"),
+            UrlKind::Clip("Slug-1".to_string())
+        );
+        assert_eq!(
+            kind("https://player.twitch.tv/?channel=chaine&parent=x"),
+            UrlKind::Live("chaine".to_string())
+        );
+
+        let parsed = MediaUrl::parse("twitch.tv/chaine/clips?range=7d").unwrap();
+        assert_eq!(parsed.kind, UrlKind::Channel("chaine".to_string()));
+        assert_eq!(
+            parsed.channel_url.as_deref(),
+            Some("https://www.twitch.tv/chaine")
+        );
+    }
+
+    #[test]
+    fn test_other_platform_links() {
+        let channel = |url: &str| {
+            let parsed = MediaUrl::parse(url).unwrap();
+            (
+                parsed.platform,
+                parsed.channel_url.unwrap(),
+                parsed.channel_id.unwrap(),
+            )
+        };
+        assert_eq!(
+            channel("https://kick.com/Streamer/videos"),
+            (
+                Platform::Kick,
+                "https://kick.com/Streamer".to_string(),
+                "streamer".to_string()
+            )
+        );
+        assert_eq!(
+            channel("https://vimeo.com/channels/staffpicks"),
+            (
+                Platform::Vimeo,
+                "https://vimeo.com/channels/staffpicks".to_string(),
+                "channels/staffpicks".to_string()
+            )
+        );
+        assert_eq!(
+            channel("https://framatube.org/c/chaine@peertube.example/videos"),
+            (
+                Platform::PeerTube,
+                "https://framatube.org/c/chaine@peertube.example".to_string(),
+                "chaine@peertube.example".to_string()
+            )
+        );
+        assert_eq!(
+            channel("https://www.dailymotion.com/Createur"),
+            (
+                Platform::Dailymotion,
+                "https://www.dailymotion.com/Createur".to_string(),
+                "createur".to_string()
+            )
+        );
+        assert_eq!(
+            channel("https://odysee.com/@chaine:7a"),
+            (
+                Platform::Odysee,
+                "https://odysee.com/@chaine:7a".to_string(),
+                "chaine:7a".to_string()
+            )
+        );
+
+        assert_eq!(
+            kind("https://vimeo.com/123456789/abcdef"),
+            UrlKind::Video("123456789".to_string())
+        );
+        assert_eq!(
+            kind("https://www.dailymotion.com/video/x7tgad0_un-titre"),
+            UrlKind::Video("x7tgad0".to_string())
+        );
+        assert_eq!(
+            kind("https://kick.com/streamer?clip=clip_01ABC"),
+            UrlKind::Clip("clip_01ABC".to_string())
+        );
+        assert_eq!(
+            kind("https://odysee.com/@chaine:7a/une-video:3"),
+            UrlKind::Video("une-video".to_string())
+        );
+        assert_eq!(
+            kind("https://tilvids.com/w/9c9de5e8-0a1e-484a-b099-e80766180a6d"),
+            UrlKind::Video("9c9de5e8-0a1e-484a-b099-e80766180a6d".to_string())
+        );
+        assert_eq!(
+            MediaUrl::parse("https://odysee.com/@chaine/video")
+                .unwrap()
+                .require_channel(),
+            Err(UrlError::NotAChannel("une vidéo"))
+        );
+    }
+
+    #[test]
+    fn test_generic_fallback() {
+        let parsed =
+            MediaUrl::parse_or_generic("https://media.example.com/@createur/videos?tri=1#haut")
+                .unwrap();
+        assert_eq!(parsed.platform, Platform::Other);
+        assert_eq!(parsed.channel_name(), Some("createur"));
+        assert_eq!(
+            MediaUrl::parse_or_generic("https://blog.example.org/a/auteur")
+                .unwrap()
+                .platform,
+            Platform::Other
+        );
+        assert_eq!(
+            parsed.channel_url.as_deref(),
+            Some("https://media.example.com/@createur/videos?tri=1")
+        );
+
+        // Les sites reconnus gardent leurs erreurs
+        assert!(matches!(
+            MediaUrl::parse_or_generic("https://www.youtube.com/feed/subscriptions"),
+            Err(UrlError::Unrecognized(_))
+        ));
+        assert_eq!(
+            MediaUrl::parse_or_generic("http://intranet/videos"),
+            Err(UrlError::UnsupportedSite("intranet".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_rejected_links() {
+        assert_eq!(MediaUrl::parse("  "), Err(UrlError::Empty));
+        assert_eq!(
+            MediaUrl::parse("https://example.com/chaine"),
+            Err(UrlError::UnsupportedSite("example.com".to_string()))
+        );
+        assert!(matches!(
+            MediaUrl::parse("https://www.youtube.com/feed/subscriptions"),
+            Err(UrlError::Unrecognized(_))
+        ));
+        assert!(matches!(
+            MediaUrl::parse("https://www.twitch.tv/directory/game/Chess"),
+            Err(UrlError::Unrecognized(_))
+        ));
+        assert_eq!(
+            MediaUrl::parse("https://youtu.be/dQw4w9WgXcQ")
+                .unwrap()
+                .require_channel(),
+            Err(UrlError::NotAChannel("une vidéo"))
+        );
+    }
+}
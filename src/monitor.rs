@@ -0,0 +1,388 @@
+use crate::archive::archive_key;
+use crate::channels::{Channel, ChannelStore, MonitorMode, ScanCursor};
+use crate::control::ControlNotice;
+use crate::downloader_queue::DownloadQueue;
+use crate::notifications::Notification;
+use crate::scanner::{ScanMode, VideoMetadata, VideoScanner};
+use anyhow::Result;
+use chrono::Utc;
+use futures::channel::mpsc;
+use gpui::App;
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Fréquence de recherche des chaînes à vérifier
+const TICK: Duration = Duration::from_secs(60);
+
+/// Vérifie périodiquement les chaînes surveillées et traite leurs nouvelles vidéos
+pub struct ChannelMonitor {
+    channels: ChannelStore,
+    scanner: Arc<VideoScanner>,
+    queue: Arc<DownloadQueue>,
+    /// Signale à l'interface les chaînes modifiées sur disque
+    notices: mpsc::UnboundedSender<ControlNotice>,
+    /// Dernière vérification de chaque chaîne, par URL
+    last_checks: Mutex<HashMap<String, Instant>>,
+    /// Vidéos déjà signalées en mode notification, par clé d'archive
+    notified: Mutex<HashSet<String>>,
+}
+
+impl ChannelMonitor {
+    /// Lance la surveillance en tâche de fond. La liste des chaînes est relue
+    /// à chaque passage pour prendre en compte les modifications. Chaque scan
+    /// enregistré est signalé, pour que l'interface recharge ses chaînes.
+    pub fn start(
+        cx: &App,
+        channels: ChannelStore,
+        scanner: Arc<VideoScanner>,
+        queue: Arc<DownloadQueue>,
+    ) -> mpsc::UnboundedReceiver<ControlNotice> {
+        let (notices, rx) = mpsc::unbounded();
+        let monitor = Self {
+            channels,
+            scanner,
+            queue,
+            notices,
+            last_checks: Mutex::new(HashMap::new()),
+            notified: Mutex::new(HashSet::new()),
+        };
+        let executor = cx.background_executor().clone();
+
+        cx.background_executor()
+            .spawn(async move {
+                loop {
+                    monitor.check_due_channels().await;
+                    executor.timer(TICK).await;
+                }
+            })
+            .detach();
+
+        rx
+    }
+
+    async fn check_due_channels(&self) {
+        let now = Instant::now();
+        for channel in self.channels.load() {
+            if !channel.monitor.is_active() {
+                continue;
+            }
+
+            let last_check = self.last_checks.lock().get(&channel.url).copied();
+            if !is_due(last_check, now, channel.monitor.interval()) {
+                continue;
+            }
+            self.last_checks.lock().insert(channel.url.clone(), now);
+
+            if let Err(error) = self.check_channel(&channel).await {
+                tracing::warn!("Surveillance de {} impossible: {}", channel.name, error);
+            }
+        }
+    }
+
+    async fn check_channel(&self, channel: &Channel) -> Result<()> {
+        tracing::info!("Vérification de la chaîne surveillée: {}", channel.name);
+        let rules = channel.rules.compile()?;
+        let (scanned, cursor) = self
+            .scanner
+            .fetch_channel_videos(channel, ScanMode::Incremental)
+            .await?;
+        let queued: HashSet<String> = self
+            .queue
+            .get_tasks()
+            .iter()
+            .map(|task| archive_key(&task.platform, &task.video_id))
+            .collect();
+        let videos = new_videos(scanned.clone(), &queued, |video| {
+            self.scanner
+                .is_video_downloaded(&channel.name, video)
+                .is_some()
+        });
+
+        // Les règles de date et la taille des fichiers demandent les
+        // métadonnées complètes, chargées pour les seules nouvelles vidéos.
+        // Les vidéos sont traitées de la plus ancienne à la plus récente.
+        let today = Utc::now().date_naive();
+        let mut handled = Vec::new();
+        let mut failed = HashSet::new();
+        for video in self.scanner.enrich_videos(videos).await.into_iter().rev() {
+            if !video.enriched {
+                failed.insert(video.id);
+                continue;
+            }
+            if !rules.matches(&video, today) {
+                continue;
+            }
+            if channel.monitor.mode == MonitorMode::AutoDownload {
+                if let Err(error) = self.enqueue(channel, &video).await {
+                    Notification::error(
+                        "Téléchargement automatique impossible",
+                        &format!("{}: {error}", video.title),
+                    );
+                    failed.insert(video.id);
+                    continue;
+                }
+            }
+            handled.push(video);
+        }
+
+        // Le curseur n'est enregistré qu'une fois les vidéos traitées, et
+        // reste avant la plus ancienne vidéo en échec
+        let cursor = cursor_after_failures(&channel.cursor, cursor, &scanned, &failed);
+        match self.channels.record_scan(&channel.url, &cursor) {
+            Ok(()) => {
+                self.notices
+                    .unbounded_send(ControlNotice::ChannelsChanged)
+                    .ok();
+            }
+            Err(error) => tracing::warn!("Date de scan non enregistrée: {:#}", error),
+        }
+
+        if !handled.is_empty() {
+            tracing::info!(
+                "{} nouvelle(s) vidéo(s) sur {}",
+                handled.len(),
+                channel.name
+            );
+            match channel.monitor.mode {
+                MonitorMode::NotifyOnly => self.notify_new_videos(channel, handled),
+                MonitorMode::AutoDownload => Notification::info(
+                    "Nouvelles vidéos",
+                    &format!(
+                        "{} vidéo(s) de {} ajoutée(s) à la queue",
+                        handled.len(),
+                        channel.name
+                    ),
+                ),
+            }
+        }
+
+        if !failed.is_empty() {
+            anyhow::bail!(
+                "{} vidéo(s) non traitée(s), nouvel essai à la prochaine vérification",
+                failed.len()
+            );
+        }
+        Ok(())
+    }
+
+    fn notify_new_videos(&self, channel: &Channel, videos: Vec<VideoMetadata>) {
+        let unseen: Vec<VideoMetadata> = {
+            let mut notified = self.notified.lock();
+            videos
+                .into_iter()
+                .filter(|video| notified.insert(archive_key(&video.platform(), &video.id)))
+                .collect()
+        };
+
+        match unseen.as_slice() {
+            [] => {}
+            [video] => Notification::info(
+                &format!("Nouvelle vidéo sur {}", channel.name),
+                &video.title,
+            ),
+            videos => Notification::info(
+                &format!("Nouvelles vidéos sur {}", channel.name),
+                &format!("{} vidéos à télécharger", videos.len()),
+            ),
+        }
+    }
+
+    async fn enqueue(&self, channel: &Channel, video: &VideoMetadata) -> Result<()> {
+        let storage = self.scanner.find_best_storage_path(
+            &channel.name,
+            channel.storage_root.as_deref(),
+            video.filesize_approx,
+        )?;
+        let output_path = channel.output_path(
+            &storage.path,
+            &output_file_stem(video, channel.filename_template.as_deref()),
+        );
+
+        self.queue
+            .add_download(
+                video.id.clone(),
+                video.platform(),
+                video.url.clone(),
+                video.title.clone(),
+                output_path,
+                channel.format,
+            )
+            .await
+    }
+}
+
+fn is_due(last_check: Option<Instant>, now: Instant, interval: Duration) -> bool {
+    match last_check {
+        Some(last_check) => now.duration_since(last_check) >= interval,
+        None => true,
+    }
+}
+
+/// Vidéos ni téléchargées ni déjà présentes dans la queue
+fn new_videos(
+    videos: Vec<VideoMetadata>,
+    queued: &HashSet<String>,
+    is_downloaded: impl Fn(&VideoMetadata) -> bool,
+) -> Vec<VideoMetadata> {
+    videos
+        .into_iter()
+        .filter(|video| !queued.contains(&archive_key(&video.platform(), &video.id)))
+        .filter(|video| !is_downloaded(video))
+        .collect()
+}
+
+/// Curseur à enregistrer après le traitement des vidéos `scanned` (de la plus
+/// récente à la plus ancienne). En cas d'échec, il n'avance que jusqu'à la
+/// vidéo qui précède la plus ancienne vidéo en échec, pour que le prochain
+/// scan incrémental la retrouve. Un rattrapage terminé n'est pas relancé.
+fn cursor_after_failures(
+    previous: &ScanCursor,
+    cursor: ScanCursor,
+    scanned: &[VideoMetadata],
+    failed: &HashSet<String>,
+) -> ScanCursor {
+    let Some(oldest_failure) = scanned.iter().rposition(|video| failed.contains(&video.id)) else {
+        return cursor;
+    };
+    match scanned.get(oldest_failure + 1) {
+        Some(video) => ScanCursor {
+            latest_id: Some(video.id.clone()),
+            ..cursor
+        },
+        None => ScanCursor {
+            backfilled: cursor.backfilled,
+            ..previous.clone()
+        },
+    }
+}
+
+/// Modèle de nom de fichier utilisé sans réglage de la chaîne
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "{title} [{id}]";
+
+/// Nom de fichier (sans extension) d'une vidéo téléchargée automatiquement.
+/// Le modèle accepte {title}, {id}, {date} (AAAA-MM-JJ), {uploader} et {platform}.
+pub fn output_file_stem(video: &VideoMetadata, template: Option<&str>) -> String {
+    let title: String = video.title.chars().take(150).collect();
+    let title = title.trim();
+    if title.is_empty() && template.is_none() {
+        return video.id.clone();
+    }
+
+    let date = video
+        .upload_date
+        .as_deref()
+        .and_then(|date| chrono::NaiveDate::parse_from_str(date, "%Y%m%d").ok())
+        .map(|date| date.to_string())
+        .unwrap_or_default();
+    let stem = template
+        .unwrap_or(DEFAULT_FILENAME_TEMPLATE)
+        .replace("{title}", title)
+        .replace("{id}", &video.id)
+        .replace("{date}", &date)
+        .replace("{uploader}", video.uploader.as_deref().unwrap_or_default())
+        .replace("{platform}", &video.platform());
+    let stem: String = stem
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+
+    match stem.trim() {
+        "" => video.id.clone(),
+        stem => stem.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn video(id: &str, title: &str) -> VideoMetadata {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "title": title,
+            "url": format!("https://www.youtube.com/watch?v={id}"),
+            "extractor_key": "Youtube",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_is_due() {
+        let now = Instant::now();
+        let interval = Duration::from_secs(3600);
+        assert!(is_due(None, now, interval));
+        assert!(!is_due(Some(now), now + Duration::from_secs(60), interval));
+        assert!(is_due(Some(now), now + interval, interval));
+    }
+
+    #[test]
+    fn test_new_videos_skips_queued_and_downloaded() {
+        let videos = vec![video("a", "A"), video("b", "B"), video("c", "C")];
+        let queued = HashSet::from([archive_key("youtube", "a")]);
+
+        let ids: Vec<String> = new_videos(videos, &queued, |video| video.id == "b")
+            .into_iter()
+            .map(|video| video.id)
+            .collect();
+        assert_eq!(ids, ["c"]);
+    }
+
+    #[test]
+    fn test_cursor_stops_before_failed_videos() {
+        let scanned = vec![video("c", "C"), video("b", "B"), video("a", "A")];
+        let previous = ScanCursor {
+            latest_id: Some("z".to_string()),
+            backfilled: false,
+        };
+        let cursor = ScanCursor {
+            latest_id: Some("c".to_string()),
+            backfilled: false,
+        };
+
+        let done = cursor_after_failures(&previous, cursor.clone(), &scanned, &HashSet::new());
+        assert_eq!(done, cursor);
+
+        let failed = HashSet::from(["b".to_string()]);
+        let partial = cursor_after_failures(&previous, cursor.clone(), &scanned, &failed);
+        assert_eq!(partial.latest_id.as_deref(), Some("a"));
+
+        let failed = HashSet::from(["c".to_string(), "a".to_string()]);
+        assert_eq!(
+            cursor_after_failures(&previous, cursor, &scanned, &failed),
+            previous
+        );
+
+        // Échec de la plus ancienne vidéo d'un rattrapage: il reste marqué fait
+        let backfilled = ScanCursor {
+            latest_id: Some("c".to_string()),
+            backfilled: true,
+        };
+        let failed = HashSet::from(["a".to_string()]);
+        let cursor = cursor_after_failures(&ScanCursor::default(), backfilled, &scanned, &failed);
+        assert_eq!(cursor.latest_id, None);
+        assert!(cursor.backfilled);
+    }
+
+    #[test]
+    fn test_output_file_stem() {
+        assert_eq!(
+            output_file_stem(&video("abc", "Live: Q&A / partie 2?"), None),
+            "Live_ Q&A _ partie 2_ [abc]"
+        );
+        assert_eq!(output_file_stem(&video("abc", "  "), None), "abc");
+
+        let mut dated = video("abc", "Titre");
+        dated.upload_date = Some("20240315".to_string());
+        assert_eq!(
+            output_file_stem(&dated, Some("{date} - {title}")),
+            "2024-03-15 - Titre"
+        );
+        assert_eq!(output_file_stem(&dated, Some("{uploader}")), "abc");
+    }
+}
@@ -0,0 +1,36 @@
+use std::path::PathBuf;
+
+/// Dossier de données de l'application ($XDG_DATA_HOME/ndownloader)
+pub fn data_dir() -> PathBuf {
+    xdg_dir("XDG_DATA_HOME", ".local/share")
+}
+
+/// Dossier de configuration de l'application ($XDG_CONFIG_HOME/ndownloader)
+pub fn config_dir() -> PathBuf {
+    xdg_dir("XDG_CONFIG_HOME", ".config")
+}
+
+/// Dossier de cache de l'application ($XDG_CACHE_HOME/ndownloader)
+pub fn cache_dir() -> PathBuf {
+    xdg_dir("XDG_CACHE_HOME", ".cache")
+}
+
+/// Dossier d'état de l'application, journaux compris ($XDG_STATE_HOME/ndownloader)
+pub fn state_dir() -> PathBuf {
+    xdg_dir("XDG_STATE_HOME", ".local/state")
+}
+
+/// Dossier personnel de l'utilisateur
+pub fn home_dir() -> PathBuf {
+    std::env::var_os("HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir)
+}
+
+fn xdg_dir(variable: &str, home_fallback: &str) -> PathBuf {
+    std::env::var_os(variable)
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(home_fallback)))
+        .unwrap_or_else(std::env::temp_dir)
+        .join("ndownloader")
+}
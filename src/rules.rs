@@ -0,0 +1,287 @@
+use crate::scanner::VideoMetadata;
+use chrono::NaiveDate;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Règles de sélection des vidéos d'une chaîne
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChannelRules {
+    /// Le titre doit correspondre à au moins une de ces expressions (si non vide)
+    pub title_include: Vec<String>,
+    /// Le titre ne doit correspondre à aucune de ces expressions
+    pub title_exclude: Vec<String>,
+    pub min_duration_secs: Option<u64>,
+    pub max_duration_secs: Option<u64>,
+    pub uploaded_after: Option<NaiveDate>,
+    pub uploaded_before: Option<NaiveDate>,
+    /// Ignore les vidéos publiées il y a plus de N jours
+    pub max_age_days: Option<u32>,
+    /// Uniquement les rediffusions et vidéos complètes: pas de clips, shorts ni temps forts
+    pub only_vods: bool,
+    /// Ignore les premières et les directs en cours
+    pub skip_live: bool,
+}
+
+#[derive(Debug, Error)]
+#[error("Expression régulière invalide \"{pattern}\": {source}")]
+pub struct RuleError {
+    pattern: String,
+    source: regex::Error,
+}
+
+/// Raison pour laquelle une vidéo n'est pas retenue
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum Rejection {
+    #[error("Titre sans mot-clé requis")]
+    TitleNotIncluded,
+    #[error("Titre exclu par « {0} »")]
+    TitleExcluded(String),
+    #[error("Trop courte")]
+    TooShort,
+    #[error("Trop longue")]
+    TooLong,
+    #[error("Publiée trop tôt")]
+    TooOld,
+    #[error("Publiée trop tard")]
+    TooRecent,
+    #[error("Clip, short ou temps fort")]
+    NotVod,
+    #[error("Première ou direct en cours")]
+    Live,
+}
+
+impl ChannelRules {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Compile les expressions régulières une fois pour toutes
+    pub fn compile(&self) -> Result<CompiledRules, RuleError> {
+        let compile_all = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|pattern| {
+                    RegexBuilder::new(pattern)
+                        .case_insensitive(true)
+                        .build()
+                        .map_err(|source| RuleError {
+                            pattern: pattern.clone(),
+                            source,
+                        })
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        Ok(CompiledRules {
+            include: compile_all(&self.title_include)?,
+            exclude: compile_all(&self.title_exclude)?,
+            rules: self.clone(),
+        })
+    }
+}
+
+pub struct CompiledRules {
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+    rules: ChannelRules,
+}
+
+impl CompiledRules {
+    /// Évalue une vidéo. Une information absente des métadonnées (durée, date)
+    /// ne fait pas rejeter la vidéo.
+    pub fn evaluate(&self, video: &VideoMetadata, today: NaiveDate) -> Result<(), Rejection> {
+        let rules = &self.rules;
+
+        if rules.skip_live && is_live_or_upcoming(video) {
+            return Err(Rejection::Live);
+        }
+        if rules.only_vods && !is_vod(video) {
+            return Err(Rejection::NotVod);
+        }
+
+        if !self.include.is_empty() && !self.include.iter().any(|re| re.is_match(&video.title)) {
+            return Err(Rejection::TitleNotIncluded);
+        }
+        if let Some(re) = self.exclude.iter().find(|re| re.is_match(&video.title)) {
+            return Err(Rejection::TitleExcluded(re.as_str().to_string()));
+        }
+
+        if let Some(duration) = video.duration {
+            if rules
+                .min_duration_secs
+                .is_some_and(|min| duration < min as f64)
+            {
+                return Err(Rejection::TooShort);
+            }
+            if rules
+                .max_duration_secs
+                .is_some_and(|max| duration > max as f64)
+            {
+                return Err(Rejection::TooLong);
+            }
+        }
+
+        if let Some(date) = upload_date(video) {
+            let oldest_by_age = rules
+                .max_age_days
+                .map(|days| today - chrono::Duration::days(i64::from(days)));
+            if rules
+                .uploaded_after
+                .into_iter()
+                .chain(oldest_by_age)
+                .any(|after| date < after)
+            {
+                return Err(Rejection::TooOld);
+            }
+            if rules.uploaded_before.is_some_and(|before| date > before) {
+                return Err(Rejection::TooRecent);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn matches(&self, video: &VideoMetadata, today: NaiveDate) -> bool {
+        self.evaluate(video, today).is_ok()
+    }
+}
+
+fn upload_date(video: &VideoMetadata) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(video.upload_date.as_deref()?, "%Y%m%d").ok()
+}
+
+/// Direct en cours ou à venir (première, direct programmé)
+fn is_live_or_upcoming(video: &VideoMetadata) -> bool {
+    matches!(
+        video.live_status.as_deref(),
+        Some("is_live" | "is_upcoming" | "post_live")
+    )
+}
+
+/// Vidéo complète, par opposition aux clips et temps forts Twitch et aux
+/// shorts YouTube
+fn is_vod(video: &VideoMetadata) -> bool {
+    let url = video.url.as_str();
+    let is_clip = video.platform() == "twitchclips"
+        || url.contains("clips.twitch.tv")
+        || url.contains("/clip/")
+        || url.contains("/shorts/")
+        || video.media_type.as_deref() == Some("short");
+    let is_highlight = video
+        .video_type
+        .as_deref()
+        .is_some_and(|video_type| video_type.eq_ignore_ascii_case("highlight"));
+    !is_clip && !is_highlight
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn video(value: serde_json::Value) -> VideoMetadata {
+        let mut base = serde_json::json!({
+            "id": "abc",
+            "title": "Stream du soir",
+            "url": "https://www.twitch.tv/videos/123",
+            "extractor_key": "TwitchVod",
+        });
+        base.as_object_mut()
+            .unwrap()
+            .extend(value.as_object().unwrap().clone());
+        serde_json::from_value(base).unwrap()
+    }
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 6, 15).unwrap()
+    }
+
+    #[test]
+    fn test_title_rules() {
+        let rules = ChannelRules {
+            title_include: vec!["stream".to_string(), "speedrun".to_string()],
+            title_exclude: vec![r"\brediff".to_string()],
+            ..Default::default()
+        }
+        .compile()
+        .unwrap();
+
+        assert!(rules.matches(&video(serde_json::json!({})), today()));
+        assert_eq!(
+            rules.evaluate(&video(serde_json::json!({"title": "Podcast"})), today()),
+            Err(Rejection::TitleNotIncluded)
+        );
+        assert_eq!(
+            rules.evaluate(
+                &video(serde_json::json!({"title": "STREAM rediffusion"})),
+                today()
+            ),
+            Err(Rejection::TitleExcluded(r"\brediff".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_duration_and_date_rules() {
+        let rules = ChannelRules {
+            min_duration_secs: Some(600),
+            max_age_days: Some(30),
+            uploaded_before: NaiveDate::from_ymd_opt(2024, 6, 10),
+            ..Default::default()
+        }
+        .compile()
+        .unwrap();
+
+        let short = video(serde_json::json!({"duration": 120.0, "upload_date": "20240601"}));
+        assert_eq!(rules.evaluate(&short, today()), Err(Rejection::TooShort));
+
+        let old = video(serde_json::json!({"duration": 7200.0, "upload_date": "20240301"}));
+        assert_eq!(rules.evaluate(&old, today()), Err(Rejection::TooOld));
+
+        let recent = video(serde_json::json!({"duration": 7200.0, "upload_date": "20240612"}));
+        assert_eq!(rules.evaluate(&recent, today()), Err(Rejection::TooRecent));
+
+        // Sans durée ni date connues, la vidéo n'est pas rejetée
+        assert!(rules.matches(&video(serde_json::json!({})), today()));
+    }
+
+    #[test]
+    fn test_vod_and_live_rules() {
+        let rules = ChannelRules {
+            only_vods: true,
+            skip_live: true,
+            ..Default::default()
+        }
+        .compile()
+        .unwrap();
+
+        let clip = video(serde_json::json!({
+            "url": "https://clips.twitch.tv/SomeClip",
+            "extractor_key": "TwitchClips",
+        }));
+        assert_eq!(rules.evaluate(&clip, today()), Err(Rejection::NotVod));
+
+        let short = video(serde_json::json!({"url": "https://www.youtube.com/shorts/xyz"}));
+        assert_eq!(rules.evaluate(&short, today()), Err(Rejection::NotVod));
+
+        let highlight = video(serde_json::json!({"broadcast_type": "HIGHLIGHT"}));
+        assert_eq!(rules.evaluate(&highlight, today()), Err(Rejection::NotVod));
+        let archive = video(serde_json::json!({"video_type": "archive"}));
+        assert!(rules.matches(&archive, today()));
+
+        let premiere = video(serde_json::json!({"live_status": "is_upcoming"}));
+        assert_eq!(rules.evaluate(&premiere, today()), Err(Rejection::Live));
+
+        let past_stream = video(serde_json::json!({"live_status": "was_live"}));
+        assert!(rules.matches(&past_stream, today()));
+    }
+
+    #[test]
+    fn test_invalid_regex() {
+        let rules = ChannelRules {
+            title_exclude: vec!["(".to_string()],
+            ..Default::default()
+        };
+        assert!(rules.compile().is_err());
+    }
+}
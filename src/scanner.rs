@@ -1,11 +1,18 @@
+use crate::archive::{archive_key, DownloadArchive};
 use crate::cache::Cache;
-use anyhow::Result;
-use parking_lot::Mutex;
+use crate::channels::{Channel, ScanCursor};
+use crate::config::Config;
+use crate::feeds::{self, FeedClient};
+use crate::storage::{StorageChoice, StorageError, StoragePool};
+use anyhow::{Context, Result};
+use futures::StreamExt;
+use parking_lot::{Mutex, RwLock};
 use serde::{Deserialize, Serialize};
+use smol::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
 use std::collections::HashMap;
+use std::path::Path;
 use std::process::Stdio;
 use std::sync::Arc;
-use std::time::Duration;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct VideoMetadata {
@@ -18,59 +25,455 @@ pub struct VideoMetadata {
     pub upload_date: Option<String>,
     #[serde(default)]
     pub uploader: Option<String>,
+    /// Clé d'extracteur yt-dlp (ex: "Youtube", "TwitchVod")
+    #[serde(default)]
+    pub extractor_key: Option<String>,
+    /// Extracteur de la vidéo dans une liste à plat, où `extractor_key` est
+    /// celui de la chaîne (ex: "YoutubeTab")
+    #[serde(default)]
+    pub ie_key: Option<String>,
+    /// Taille estimée du fichier téléchargé, en octets
+    #[serde(default)]
+    pub filesize_approx: Option<u64>,
+    /// "not_live", "is_live", "is_upcoming", "was_live" ou "post_live"
+    #[serde(default)]
+    pub live_status: Option<String>,
+    /// Type de contenu YouTube ("video", "short", "livestream")
+    #[serde(default)]
+    pub media_type: Option<String>,
+    /// Type de vidéo Twitch ("archive", "highlight" ou "upload")
+    #[serde(default, alias = "broadcast_type")]
+    pub video_type: Option<String>,
+    #[serde(default)]
+    pub thumbnail: Option<String>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub chapters: Vec<Chapter>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub formats: Vec<VideoFormat>,
+    /// Métadonnées complètes chargées (formats, taille, miniature, chapitres),
+    /// et non une simple entrée de liste à plat
+    #[serde(default)]
+    pub enriched: bool,
+}
+
+/// yt-dlp écrit `null` pour les listes absentes
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Chapter {
+    pub title: String,
+    pub start_time: f64,
+    pub end_time: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct VideoFormat {
+    pub format_id: String,
+    #[serde(default)]
+    pub ext: Option<String>,
+    #[serde(default)]
+    pub height: Option<u32>,
+    #[serde(default)]
+    pub filesize: Option<u64>,
+}
+
+impl VideoMetadata {
+    /// Plateforme au sens de l'archive de téléchargement
+    pub fn platform(&self) -> String {
+        self.ie_key
+            .clone()
+            .or_else(|| self.extractor_key.clone())
+            .unwrap_or_default()
+            .to_lowercase()
+    }
+}
+
+/// Vidéo scannée avec son état local
+#[derive(Debug, Clone, Serialize)]
+pub struct ScannedVideo {
+    #[serde(flatten)]
+    pub video: VideoMetadata,
+    /// Fichier local ou entrée d'archive correspondante
+    pub downloaded: Option<String>,
+    /// Raison pour laquelle les règles de la chaîne écartent la vidéo
+    pub rejected: Option<String>,
+}
+
+impl ScannedVideo {
+    /// Ni téléchargée ni écartée par les règles
+    pub fn is_new(&self) -> bool {
+        self.downloaded.is_none() && self.rejected.is_none()
+    }
+}
+
+/// Nombre de vidéos par page de scan
+pub const PAGE_SIZE: usize = 30;
+
+/// Nombre maximal de vidéos parcourues par un scan incrémental dont la
+/// dernière vidéo connue a disparu
+const INCREMENTAL_LIMIT: usize = 300;
+
+/// Étendue d'un scan de chaîne
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanMode {
+    /// `count` vidéos à partir de la position `start` (0 = la plus récente)
+    Page { start: usize, count: usize },
+    /// Nouvelles vidéos jusqu'à la dernière vue au scan précédent
+    Incremental,
+    /// Tout l'historique de la chaîne
+    Backfill,
+}
+
+impl ScanMode {
+    pub fn first_page() -> Self {
+        ScanMode::Page {
+            start: 0,
+            count: PAGE_SIZE,
+        }
+    }
+
+    /// Sans curseur, un scan incrémental parcourt tout l'historique si la
+    /// chaîne le demande, sinon la première page
+    fn for_channel(self, channel: &Channel) -> Self {
+        match self {
+            ScanMode::Incremental if channel.cursor.latest_id.is_none() => {
+                if channel.backfill && !channel.cursor.backfilled {
+                    ScanMode::Backfill
+                } else {
+                    Self::first_page()
+                }
+            }
+            mode => mode,
+        }
+    }
+
+    /// Curseur après un scan demandé comme `self` et effectué comme `scanned`.
+    /// Seuls les scans incrémentaux avancent la dernière vidéo connue ou marquent
+    /// l'historique comme parcouru: un simple affichage ne doit pas masquer à la
+    /// surveillance des vidéos non traitées.
+    fn advance(
+        self,
+        scanned: ScanMode,
+        cursor: &ScanCursor,
+        videos: &[VideoMetadata],
+    ) -> ScanCursor {
+        let mut cursor = cursor.clone();
+        if let (ScanMode::Incremental, Some(newest)) = (self, videos.first()) {
+            cursor.latest_id = Some(newest.id.clone());
+        }
+        if self == ScanMode::Incremental && scanned == ScanMode::Backfill {
+            cursor.backfilled = true;
+        }
+        cursor
+    }
 }
 
 pub struct VideoScanner {
-    storage_paths: Vec<String>,
+    storage: RwLock<StoragePool>,
     cache: Cache<Vec<VideoMetadata>>,
+    /// Métadonnées complètes par URL de vidéo
+    details_cache: Cache<VideoMetadata>,
     file_durations_cache: Arc<Mutex<HashMap<String, f64>>>,
+    archive: Arc<DownloadArchive>,
+    feeds: FeedClient,
 }
 
 impl VideoScanner {
-    pub fn new() -> Self {
+    pub fn new(config: &Config, archive: Arc<DownloadArchive>) -> Self {
         Self {
-            storage_paths: vec![
-                "/run/mount/ve_stock_1".to_string(),
-                "/run/mount/ve_stock_2".to_string(),
-                "/run/mount/ve_ext_1".to_string(),
-            ],
+            storage: RwLock::new(StoragePool::from_config(config)),
             cache: Cache::new(
-                std::path::PathBuf::from("/tmp/ndownload_videos_cache.json"),
-                Duration::from_secs(300),
+                config.cache_dir.join("videos_cache.json"),
+                config.scan_cache_ttl(),
+            ),
+            details_cache: Cache::new(
+                config.cache_dir.join("video_details_cache.json"),
+                config.scan_cache_ttl(),
             ),
             file_durations_cache: Arc::new(Mutex::new(HashMap::new())),
+            archive,
+            feeds: FeedClient::default(),
         }
     }
 
-    /// Scanne les vidéos disponibles d'une chaîne avec yt-dlp
-    pub async fn scan_channel_videos(&self, channel_url: &str) -> Result<Vec<VideoMetadata>> {
-        tracing::info!("Scan des vidéos de: {}", channel_url);
+    /// Applique de nouveaux réglages de stockage
+    pub fn set_storage(&self, config: &Config) {
+        *self.storage.write() = StoragePool::from_config(config);
+    }
 
-        // Pour Twitch, s'assurer qu'on utilise l'URL /videos pour les VODs
-        let url = if channel_url.contains("twitch.tv") && !channel_url.contains("/videos") {
-            format!("{}/videos", channel_url.trim_end_matches('/'))
-        } else {
-            channel_url.to_string()
-        };
+    /// Liste à plat les vidéos d'une chaîne avec yt-dlp, de la plus récente à
+    /// la plus ancienne, sans résoudre les formats de chaque vidéo (voir
+    /// `enrich_video`). Le scan s'arrête avant `stop_at`.
+    pub async fn scan_channel_videos(
+        &self,
+        channel: &Channel,
+        mode: ScanMode,
+        stop_at: Option<&str>,
+    ) -> Result<Vec<VideoMetadata>> {
+        tracing::info!("Scan des vidéos de: {} ({:?})", channel.url, mode);
 
+        let extractor = channel.platform.extractor();
+        let url = extractor.listing_url(channel);
         tracing::info!("URL utilisée: {}", url);
 
-        // Vérifier le cache
-        if let Some(videos) = self.cache.get(&url) {
+        // Seules les pages sont mises en cache: les scans incrémentaux doivent
+        // voir les dernières vidéos
+        let cache_key = match mode {
+            ScanMode::Page { start, count } => Some(format!("{url}#{start}+{count}")),
+            ScanMode::Incremental | ScanMode::Backfill => None,
+        };
+        if let Some(videos) = cache_key.as_ref().and_then(|key| self.cache.get(key)) {
             tracing::info!("Utilisation du cache pour: {}", url);
             return Ok(videos);
         }
 
-        let output = smol::process::Command::new("yt-dlp")
+        let mut command = smol::process::Command::new("yt-dlp");
+        command
             .arg("--skip-download")
             .arg("--no-write-info-json")
             .arg("--no-write-playlist-metafiles")
-            .arg("--dump-json")
-            .arg("--playlist-end")
-            .arg("30") // Limiter à 30 vidéos pour garder de la vitesse
+            .arg("--flat-playlist")
+            .arg("--dump-json");
+        match mode {
+            ScanMode::Page { start, count } => {
+                command
+                    .arg("--playlist-start")
+                    .arg((start + 1).to_string())
+                    .arg("--playlist-end")
+                    .arg((start + count).to_string());
+            }
+            ScanMode::Incremental => {
+                command
+                    .arg("--playlist-end")
+                    .arg(INCREMENTAL_LIMIT.to_string());
+            }
+            ScanMode::Backfill => {}
+        }
+        let mut child = command
             .arg(&url)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+        let stdout = child
+            .stdout
+            .take()
+            .context("Sortie de yt-dlp indisponible")?;
+        let mut stderr = child
+            .stderr
+            .take()
+            .context("Sortie de yt-dlp indisponible")?;
+
+        // Chaque ligne est un JSON, lu au fil de l'eau pour pouvoir s'arrêter
+        // dès la première vidéo connue
+        let read_videos = async {
+            let mut videos = Vec::new();
+            let mut lines = BufReader::new(stdout).lines();
+            while let Some(line) = lines.next().await {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                match serde_json::from_str::<VideoMetadata>(&line)
+                    .map(|video| extractor.normalize_video(video))
+                {
+                    Ok(video) if Some(video.id.as_str()) == stop_at => {
+                        tracing::info!("Vidéo déjà connue atteinte: {}", video.id);
+                        child.kill().ok();
+                        return anyhow::Ok((videos, true));
+                    }
+                    Ok(video) => {
+                        tracing::debug!("Vidéo: {} - durée: {:?}", video.title, video.duration);
+                        videos.push(video);
+                    }
+                    Err(e) => {
+                        tracing::warn!("Erreur parsing JSON: {} - ligne: {}", e, line);
+                    }
+                }
+            }
+            anyhow::Ok((videos, false))
+        };
+        let read_errors = async {
+            let mut errors = String::new();
+            stderr.read_to_string(&mut errors).await.ok();
+            errors
+        };
+        let (videos, errors) = futures::join!(read_videos, read_errors);
+        let (videos, stopped) = videos?;
+        let status = child.status().await?;
+
+        if !stopped && !status.success() {
+            anyhow::bail!("yt-dlp a échoué: {errors}");
+        }
+
+        tracing::info!("Trouvé {} vidéos", videos.len());
+
+        // Mettre à jour le cache
+        if let Some(key) = cache_key {
+            self.cache.set(key, videos.clone());
+        }
+
+        Ok(videos)
+    }
+
+    /// Scanne une chaîne et indique pour chaque vidéo si elle est déjà
+    /// téléchargée ou écartée par les règles de la chaîne. Avec `details`, les
+    /// métadonnées complètes de chaque vidéo sont chargées avant les règles.
+    /// Le curseur n'est pas avancé: seule la surveillance, qui traite les
+    /// nouvelles vidéos, l'enregistre.
+    pub async fn scan_channel(
+        &self,
+        channel: &Channel,
+        mode: ScanMode,
+        details: bool,
+    ) -> Result<Vec<ScannedVideo>> {
+        let rules = channel.rules.compile()?;
+        let today = chrono::Utc::now().date_naive();
+        let (mut videos, _) = self.fetch_channel_videos(channel, mode).await?;
+        if details {
+            videos = self.enrich_videos(videos).await;
+        }
+
+        Ok(videos
+            .into_iter()
+            .map(|video| ScannedVideo {
+                downloaded: self.is_video_downloaded(&channel.name, &video),
+                rejected: rules
+                    .evaluate(&video, today)
+                    .err()
+                    .map(|rejection| rejection.to_string()),
+                video,
+            })
+            .collect())
+    }
+
+    /// Vidéos d'une chaîne selon `mode`, avec le curseur à enregistrer
+    pub async fn fetch_channel_videos(
+        &self,
+        channel: &Channel,
+        mode: ScanMode,
+    ) -> Result<(Vec<VideoMetadata>, ScanCursor)> {
+        let scanned = mode.for_channel(channel);
+        if scanned == ScanMode::Incremental {
+            if let Some(videos) = self.scan_feed(channel).await {
+                let cursor = mode.advance(scanned, &channel.cursor, &videos);
+                return Ok((videos, cursor));
+            }
+        }
+
+        let stop_at = match scanned {
+            ScanMode::Incremental => channel.cursor.latest_id.as_deref(),
+            ScanMode::Page { .. } | ScanMode::Backfill => None,
+        };
+        let videos = self.scan_channel_videos(channel, scanned, stop_at).await?;
+        let cursor = mode.advance(scanned, &channel.cursor, &videos);
+        Ok((videos, cursor))
+    }
+
+    /// Nouvelles vidéos d'une chaîne YouTube d'après son flux Atom. `None` si le
+    /// flux est indisponible ou ne remonte pas jusqu'à la dernière vidéo connue,
+    /// auquel cas yt-dlp reprend le scan.
+    async fn scan_feed(&self, channel: &Channel) -> Option<Vec<VideoMetadata>> {
+        if !channel.platform.extractor().has_feed() {
+            return None;
+        }
+        let channel_id = channel.channel_id.as_deref()?;
+        let latest_id = channel.cursor.latest_id.as_deref()?;
+
+        let feed = match self.feeds.fetch(channel_id).await {
+            Ok(feed) => feed,
+            Err(error) => {
+                tracing::warn!("Flux de {} indisponible: {:#}", channel.name, error);
+                return None;
+            }
+        };
+        let videos = feeds::videos_since(feed, latest_id);
+        if videos.is_none() {
+            tracing::info!(
+                "{}: dernière vidéo connue absente du flux, scan complet",
+                channel.name
+            );
+        }
+        videos
+    }
+
+    /// Métadonnées complètes d'une vidéo listée à plat
+    pub async fn enrich_video(&self, video: &VideoMetadata) -> Result<VideoMetadata> {
+        if video.enriched {
+            return Ok(video.clone());
+        }
+        if let Some(details) = self.details_cache.get(&video.url) {
+            return Ok(details);
+        }
+
+        let mut details = self.fetch_video(&video.url).await?;
+        // Garder le lien de la page, qui identifie la ligne dans la liste
+        details.url = video.url.clone();
+        self.details_cache.set(video.url.clone(), details.clone());
+        Ok(details)
+    }
+
+    /// Complète une à une les vidéos listées à plat. Une vidéo dont les détails
+    /// n'ont pas pu être chargés est gardée telle quelle.
+    pub async fn enrich_videos(&self, videos: Vec<VideoMetadata>) -> Vec<VideoMetadata> {
+        let mut enriched = Vec::with_capacity(videos.len());
+        for video in videos {
+            match self.enrich_video(&video).await {
+                Ok(details) => enriched.push(details),
+                Err(error) => {
+                    tracing::warn!("Détails de {} indisponibles: {:#}", video.url, error);
+                    enriched.push(video);
+                }
+            }
+        }
+        enriched
+    }
+
+    /// Récupère les métadonnées complètes d'une seule vidéo
+    pub async fn fetch_video(&self, video_url: &str) -> Result<VideoMetadata> {
+        let output = smol::process::Command::new("yt-dlp")
+            .arg("--skip-download")
+            .arg("--no-playlist")
+            .arg("--dump-json")
+            .arg(video_url)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("yt-dlp a échoué: {error}");
+        }
+
+        let mut video: VideoMetadata = serde_json::from_slice(&output.stdout)?;
+        video.enriched = true;
+        Ok(video)
+    }
+
+    /// Identifiant stable (`UC...`) d'une chaîne désignée par handle ou
+    /// ancienne URL, lu dans les métadonnées de yt-dlp
+    pub async fn resolve_channel_id(channel_url: &str) -> Result<String> {
+        #[derive(Deserialize)]
+        struct PlaylistInfo {
+            channel_id: Option<String>,
+        }
+
+        let output = smol::process::Command::new("yt-dlp")
+            .arg("--flat-playlist")
+            .arg("--playlist-items")
+            .arg("1")
+            .arg("--dump-single-json")
+            .arg(channel_url)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
             .output()
             .await?;
 
@@ -79,36 +482,45 @@ impl VideoScanner {
             anyhow::bail!("yt-dlp a échoué: {error}");
         }
 
-        let stdout = String::from_utf8(output.stdout)?;
-        let mut videos = Vec::new();
+        let info: PlaylistInfo = serde_json::from_slice(&output.stdout)?;
+        info.channel_id
+            .filter(|id| !id.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Identifiant de chaîne absent pour {channel_url}"))
+    }
 
-        // Chaque ligne est un JSON
-        for line in stdout.lines() {
-            if line.trim().is_empty() {
-                continue;
+    /// Complète l'identifiant de la chaîne s'il manque. Un échec est seulement
+    /// journalisé: la chaîne reste utilisable par son nom.
+    pub async fn fill_channel_id(channel: &mut Channel) {
+        if channel.channel_id.is_some() {
+            return;
+        }
+        match Self::resolve_channel_id(&channel.url).await {
+            Ok(id) => channel.channel_id = Some(id),
+            Err(error) => {
+                tracing::warn!("Identifiant de {} non résolu: {:#}", channel.name, error)
             }
+        }
+    }
 
-            match serde_json::from_str::<VideoMetadata>(line) {
-                Ok(video) => {
-                    tracing::debug!("Vidéo: {} - durée: {:?}", video.title, video.duration);
-                    videos.push(video);
-                }
-                Err(e) => {
-                    tracing::warn!("Erreur parsing JSON: {} - ligne: {}", e, line);
+    /// Vérifie si une vidéo est déjà téléchargée: d'abord dans l'archive,
+    /// puis par comparaison de durée pour les fichiers antérieurs à l'archive
+    pub fn is_video_downloaded(&self, channel_name: &str, video: &VideoMetadata) -> Option<String> {
+        if let Some(entry) = self.archive.get(&video.platform(), &video.id) {
+            match entry.path {
+                Some(path) if path.exists() => return Some(path.to_string_lossy().to_string()),
+                Some(path) => {
+                    tracing::debug!("Fichier archivé introuvable: {}", path.display());
                 }
+                // Entrée importée d'une archive yt-dlp: pas de chemin connu
+                None => return Some(archive_key(&entry.platform, &entry.video_id)),
             }
         }
 
-        tracing::info!("Trouvé {} vidéos", videos.len());
-
-        // Mettre à jour le cache
-        self.cache.set(url.clone(), videos.clone());
-
-        Ok(videos)
+        self.find_by_duration(channel_name, video.duration)
     }
 
-    /// Vérifie si une vidéo est déjà téléchargée en comparant la durée
-    pub fn is_video_downloaded(&self, channel_name: &str, duration: Option<f64>) -> Option<String> {
+    /// Cherche un fichier local de durée identique (tolérance de 5 secondes)
+    fn find_by_duration(&self, channel_name: &str, duration: Option<f64>) -> Option<String> {
         let Some(target_duration) = duration else {
             tracing::debug!("Pas de durée cible, impossible de vérifier");
             return None;
@@ -120,12 +532,13 @@ impl VideoScanner {
             channel_name
         );
 
-        for storage_path in &self.storage_paths {
-            let channel_path = format!("{storage_path}/{channel_name}");
+        let roots = self.storage.read().roots().to_vec();
+        for root in &roots {
+            let channel_path = root.path.join(channel_name);
 
             // Vérifier si le dossier existe
             if let Ok(entries) = std::fs::read_dir(&channel_path) {
-                tracing::debug!("Scan du dossier: {}", channel_path);
+                tracing::debug!("Scan du dossier: {}", channel_path.display());
                 for entry in entries.flatten() {
                     let path = entry.path();
                     if !path.is_file() {
@@ -166,7 +579,7 @@ impl VideoScanner {
                     }
                 }
             } else {
-                tracing::debug!("Dossier n'existe pas: {}", channel_path);
+                tracing::debug!("Dossier n'existe pas: {}", channel_path.display());
             }
         }
 
@@ -194,15 +607,111 @@ impl VideoScanner {
         duration_str.trim().parse::<f64>().ok()
     }
 
-    /// Trouve le meilleur disque de stockage (celui avec le plus d'espace)
-    pub fn find_best_storage_path(&self) -> Result<String> {
-        // Pour l'instant, retourner le premier disponible
-        for path in &self.storage_paths {
-            if std::path::Path::new(path).exists() {
-                return Ok(path.clone());
-            }
-        }
+    /// Trouve le meilleur disque de stockage selon la stratégie configurée,
+    /// en vérifiant que la taille estimée de la vidéo y tient
+    pub fn find_best_storage_path(
+        &self,
+        channel_name: &str,
+        channel_root: Option<&Path>,
+        estimated_size: Option<u64>,
+    ) -> Result<StorageChoice, StorageError> {
+        self.storage
+            .read()
+            .select(channel_name, channel_root, estimated_size)
+    }
+}
 
-        anyhow::bail!("Aucun disque de stockage disponible")
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn video(id: &str) -> VideoMetadata {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "title": id,
+            "url": format!("https://www.youtube.com/watch?v={id}"),
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_cursor_only_moves_on_incremental_scans() {
+        let videos = [video("b"), video("a")];
+        let page = ScanMode::first_page();
+        assert_eq!(
+            page.advance(page, &ScanCursor::default(), &videos),
+            ScanCursor::default()
+        );
+
+        // Premier scan incrémental, effectué comme une première page
+        let cursor = ScanMode::Incremental.advance(page, &ScanCursor::default(), &videos);
+        assert_eq!(cursor.latest_id.as_deref(), Some("b"));
+        assert!(!cursor.backfilled);
+
+        // Rien de nouveau: le curseur reste sur la dernière vidéo connue
+        let incremental = ScanMode::Incremental;
+        assert_eq!(incremental.advance(incremental, &cursor, &[]), cursor);
+
+        // Affichage de tout l'historique: la surveillance fera son propre rattrapage
+        let backfill = ScanMode::Backfill;
+        assert_eq!(
+            backfill.advance(backfill, &cursor, &[video("c"), video("b")]),
+            cursor
+        );
+
+        // Rattrapage de la surveillance
+        let cursor = incremental.advance(backfill, &ScanCursor::default(), &videos);
+        assert_eq!(cursor.latest_id.as_deref(), Some("b"));
+        assert!(cursor.backfilled);
+    }
+
+    #[test]
+    fn test_flat_entries_keep_the_video_extractor() {
+        let flat: VideoMetadata = serde_json::from_value(serde_json::json!({
+            "_type": "url",
+            "ie_key": "Youtube",
+            "extractor_key": "YoutubeTab",
+            "id": "abc",
+            "title": "Titre",
+            "url": "https://www.youtube.com/watch?v=abc",
+            "duration": 61.0,
+        }))
+        .unwrap();
+        assert_eq!(flat.platform(), "youtube");
+        assert!(!flat.enriched);
+
+        let full: VideoMetadata = serde_json::from_value(serde_json::json!({
+            "extractor_key": "TwitchVod",
+            "id": "v1",
+            "title": "VOD",
+            "url": "https://www.twitch.tv/videos/1",
+            "chapters": null,
+            "formats": [{"format_id": "1080p60", "ext": "mp4", "height": 1080, "filesize": null}],
+        }))
+        .unwrap();
+        assert_eq!(full.platform(), "twitchvod");
+        assert!(full.chapters.is_empty());
+        assert_eq!(full.formats[0].height, Some(1080));
+    }
+
+    #[test]
+    fn test_incremental_scan_without_cursor() {
+        let mut channel = Channel::from_url("https://www.twitch.tv/chaine").unwrap();
+        assert_eq!(
+            ScanMode::Incremental.for_channel(&channel),
+            ScanMode::first_page()
+        );
+
+        channel.backfill = true;
+        assert_eq!(
+            ScanMode::Incremental.for_channel(&channel),
+            ScanMode::Backfill
+        );
+
+        channel.cursor.latest_id = Some("123".to_string());
+        assert_eq!(
+            ScanMode::Incremental.for_channel(&channel),
+            ScanMode::Incremental
+        );
     }
 }
@@ -0,0 +1,311 @@
+use crate::config::{Config, StorageRole, StorageRoot, StorageStrategy};
+use crate::download_progress::format_size;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum StorageError {
+    #[error("Aucun disque de stockage disponible")]
+    NoStorageAvailable,
+    #[error(
+        "Espace disque insuffisant: {} nécessaires, {} disponibles au mieux",
+        format_size(*.required),
+        format_size(*.available)
+    )]
+    InsufficientSpace { required: u64, available: u64 },
+}
+
+/// Emplacement retenu pour un téléchargement
+#[derive(Debug, Clone, PartialEq)]
+pub struct StorageChoice {
+    pub path: PathBuf,
+    /// L'emplacement préféré par la stratégie n'avait pas assez d'espace
+    pub rerouted: bool,
+}
+
+/// Emplacement accessible en écriture avec son espace libre
+#[derive(Debug)]
+struct Candidate {
+    path: PathBuf,
+    role: StorageRole,
+    available: u64,
+    has_channel: bool,
+}
+
+/// Emplacements de stockage et stratégie de sélection
+pub struct StoragePool {
+    roots: Vec<StorageRoot>,
+    strategy: StorageStrategy,
+    reserve_bytes: u64,
+    round_robin: AtomicUsize,
+}
+
+impl StoragePool {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            roots: config.storage.clone(),
+            strategy: config.defaults.storage_strategy,
+            reserve_bytes: config.storage_reserve_bytes(),
+            round_robin: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn roots(&self) -> &[StorageRoot] {
+        &self.roots
+    }
+
+    /// Choisit l'emplacement d'un téléchargement de taille estimée `required`.
+    /// L'emplacement `preferred` de la chaîne passe avant la stratégie s'il a la place ;
+    /// un emplacement de débordement n'est utilisé que si aucun principal ne convient.
+    pub fn select(
+        &self,
+        channel_name: &str,
+        preferred: Option<&Path>,
+        required: Option<u64>,
+    ) -> Result<StorageChoice, StorageError> {
+        // L'emplacement de la chaîne peut se trouver hors des emplacements configurés
+        let extra_root = preferred
+            .filter(|path| !self.roots.iter().any(|root| root.path == *path))
+            .map(|path| StorageRoot {
+                path: path.to_path_buf(),
+                role: StorageRole::Primary,
+            });
+        let candidates: Vec<Candidate> = self
+            .roots
+            .iter()
+            .chain(extra_root.as_ref())
+            .filter(|root| root.role.is_writable() && root.path.exists())
+            .filter_map(|root| match fs4::statvfs(&root.path) {
+                Ok(stats) => Some(Candidate {
+                    path: root.path.clone(),
+                    role: root.role,
+                    available: stats.available_space(),
+                    has_channel: root.path.join(channel_name).is_dir(),
+                }),
+                Err(error) => {
+                    tracing::warn!(
+                        "Espace libre illisible pour {}: {}",
+                        root.path.display(),
+                        error
+                    );
+                    None
+                }
+            })
+            .collect();
+
+        self.choose(&candidates, preferred, required)
+    }
+
+    fn choose(
+        &self,
+        candidates: &[Candidate],
+        preferred_root: Option<&Path>,
+        required: Option<u64>,
+    ) -> Result<StorageChoice, StorageError> {
+        if candidates.is_empty() {
+            return Err(StorageError::NoStorageAvailable);
+        }
+
+        let required = required.unwrap_or(0);
+        let needed = required.saturating_add(self.reserve_bytes);
+        if let Some(preferred_root) = preferred_root {
+            if let Some(candidate) = candidates
+                .iter()
+                .find(|c| c.path == preferred_root && c.available >= needed)
+            {
+                return Ok(StorageChoice {
+                    path: candidate.path.clone(),
+                    rerouted: false,
+                });
+            }
+        }
+
+        let round = self.round_robin.load(Ordering::Relaxed);
+        let by_role = |role: StorageRole, needed: u64| -> Vec<&Candidate> {
+            candidates
+                .iter()
+                .filter(|c| c.role == role && c.available >= needed)
+                .collect()
+        };
+
+        // Choix de la stratégie sans tenir compte de la taille de la vidéo
+        let preferred = match preferred_root {
+            Some(path) => Some(path.to_path_buf()),
+            None => self
+                .pick(&by_role(StorageRole::Primary, self.reserve_bytes), round)
+                .or_else(|| self.pick(&by_role(StorageRole::Overflow, self.reserve_bytes), round))
+                .map(|c| c.path.clone()),
+        };
+
+        for role in [StorageRole::Primary, StorageRole::Overflow] {
+            if let Some(candidate) = self.pick(&by_role(role, needed), round) {
+                if self.strategy == StorageStrategy::RoundRobin {
+                    self.round_robin.fetch_add(1, Ordering::Relaxed);
+                }
+                let rerouted = preferred.as_ref() != Some(&candidate.path);
+                if rerouted {
+                    tracing::info!(
+                        "Téléchargement redirigé vers {} faute d'espace",
+                        candidate.path.display()
+                    );
+                }
+                return Ok(StorageChoice {
+                    path: candidate.path.clone(),
+                    rerouted,
+                });
+            }
+        }
+
+        let available = candidates
+            .iter()
+            .map(|c| c.available.saturating_sub(self.reserve_bytes))
+            .max()
+            .unwrap_or(0);
+        Err(StorageError::InsufficientSpace {
+            required,
+            available,
+        })
+    }
+
+    fn pick<'a>(&self, candidates: &[&'a Candidate], round: usize) -> Option<&'a Candidate> {
+        let most_free = || candidates.iter().copied().max_by_key(|c| c.available);
+        match self.strategy {
+            StorageStrategy::MostFreeSpace => most_free(),
+            StorageStrategy::FillFirst => candidates.first().copied(),
+            StorageStrategy::RoundRobin => match candidates.len() {
+                0 => None,
+                len => Some(candidates[round % len]),
+            },
+            StorageStrategy::SameDiskAsChannel => candidates
+                .iter()
+                .copied()
+                .find(|c| c.has_channel)
+                .or_else(most_free),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GIB: u64 = 1024 * 1024 * 1024;
+
+    fn pool(strategy: StorageStrategy) -> StoragePool {
+        let mut config = Config::default();
+        config.defaults.storage_strategy = strategy;
+        config.defaults.storage_reserve_gib = 10;
+        StoragePool::from_config(&config)
+    }
+
+    fn candidate(path: &str, role: StorageRole, available_gib: u64) -> Candidate {
+        Candidate {
+            path: PathBuf::from(path),
+            role,
+            available: available_gib * GIB,
+            has_channel: false,
+        }
+    }
+
+    #[test]
+    fn test_most_free_space_and_fill_first() {
+        let candidates = [
+            candidate("/a", StorageRole::Primary, 50),
+            candidate("/b", StorageRole::Primary, 200),
+        ];
+
+        let choice = pool(StorageStrategy::MostFreeSpace)
+            .choose(&candidates, None, Some(GIB))
+            .unwrap();
+        assert_eq!(choice.path, PathBuf::from("/b"));
+
+        let fill_first = pool(StorageStrategy::FillFirst);
+        let choice = fill_first.choose(&candidates, None, Some(GIB)).unwrap();
+        assert_eq!(choice.path, PathBuf::from("/a"));
+        assert!(!choice.rerouted);
+
+        // /a passerait sous la réserve de 10 Gio: on passe au suivant
+        let choice = fill_first
+            .choose(&candidates, None, Some(45 * GIB))
+            .unwrap();
+        assert_eq!(choice.path, PathBuf::from("/b"));
+        assert!(choice.rerouted);
+    }
+
+    #[test]
+    fn test_round_robin_cycles() {
+        let pool = pool(StorageStrategy::RoundRobin);
+        let candidates = [
+            candidate("/a", StorageRole::Primary, 100),
+            candidate("/b", StorageRole::Primary, 100),
+        ];
+
+        let paths: Vec<PathBuf> = (0..3)
+            .map(|_| pool.choose(&candidates, None, None).unwrap().path)
+            .collect();
+        assert_eq!(paths, ["/a", "/b", "/a"].map(PathBuf::from));
+    }
+
+    #[test]
+    fn test_same_disk_and_overflow_reroute() {
+        let pool = pool(StorageStrategy::SameDiskAsChannel);
+        let mut candidates = [
+            candidate("/a", StorageRole::Primary, 500),
+            candidate("/b", StorageRole::Primary, 30),
+            candidate("/overflow", StorageRole::Overflow, 1000),
+        ];
+        candidates[1].has_channel = true;
+
+        let choice = pool.choose(&candidates, None, Some(5 * GIB)).unwrap();
+        assert_eq!(choice.path, PathBuf::from("/b"));
+        assert!(!choice.rerouted);
+
+        // Trop gros pour /b: redirigé vers le principal le plus libre
+        let choice = pool.choose(&candidates, None, Some(100 * GIB)).unwrap();
+        assert_eq!(choice.path, PathBuf::from("/a"));
+        assert!(choice.rerouted);
+
+        // Trop gros pour tous les principaux: débordement
+        let choice = pool.choose(&candidates, None, Some(600 * GIB)).unwrap();
+        assert_eq!(choice.path, PathBuf::from("/overflow"));
+    }
+
+    #[test]
+    fn test_channel_root_comes_first() {
+        let pool = pool(StorageStrategy::MostFreeSpace);
+        let candidates = [
+            candidate("/a", StorageRole::Primary, 500),
+            candidate("/chaine", StorageRole::Primary, 30),
+        ];
+        let preferred = Some(Path::new("/chaine"));
+
+        let choice = pool.choose(&candidates, preferred, Some(5 * GIB)).unwrap();
+        assert_eq!(choice.path, PathBuf::from("/chaine"));
+        assert!(!choice.rerouted);
+
+        let choice = pool
+            .choose(&candidates, preferred, Some(100 * GIB))
+            .unwrap();
+        assert_eq!(choice.path, PathBuf::from("/a"));
+        assert!(choice.rerouted);
+    }
+
+    #[test]
+    fn test_refuses_when_nothing_fits() {
+        let pool = pool(StorageStrategy::MostFreeSpace);
+        let candidates = [candidate("/a", StorageRole::Primary, 20)];
+
+        assert_eq!(
+            pool.choose(&candidates, None, Some(15 * GIB)),
+            Err(StorageError::InsufficientSpace {
+                required: 15 * GIB,
+                available: 10 * GIB,
+            })
+        );
+        assert_eq!(
+            pool.choose(&[], None, None),
+            Err(StorageError::NoStorageAvailable)
+        );
+    }
+}
@@ -0,0 +1,437 @@
+use crate::channels::{channels_to_json, parse_channels, Channel, Platform};
+use anyhow::{Context, Result};
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Formats de fichiers d'abonnements reconnus à l'import
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// Liste OPML (lecteurs RSS, ancien export YouTube)
+    Opml,
+    /// subscriptions.csv de Google Takeout
+    TakeoutCsv,
+    /// Chaînes suivies sur Twitch (réponse JSON de l'API Helix)
+    TwitchFollows,
+    /// Export JSON de NDownloader, réglages compris
+    Json,
+    /// Une URL par ligne
+    UrlList,
+}
+
+impl ImportFormat {
+    /// Devine le format d'après l'extension, puis d'après le contenu
+    pub fn detect(path: &Path, content: &str) -> Self {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let content = content.trim_start_matches('\u{feff}').trim_start();
+        let first_line = content.lines().next().unwrap_or_default();
+
+        match extension.as_str() {
+            "opml" | "xml" => Self::Opml,
+            "csv" => Self::TakeoutCsv,
+            _ if content.starts_with('<') => Self::Opml,
+            _ if content.starts_with('{') || content.starts_with('[') => {
+                if parse_channels(content).is_ok() {
+                    Self::Json
+                } else {
+                    Self::TwitchFollows
+                }
+            }
+            _ if first_line.contains(',') && !first_line.contains("://") => Self::TakeoutCsv,
+            _ => Self::UrlList,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ImportFormat::Opml => "OPML",
+            ImportFormat::TakeoutCsv => "Google Takeout (CSV)",
+            ImportFormat::TwitchFollows => "Chaînes suivies Twitch",
+            ImportFormat::Json => "Export NDownloader",
+            ImportFormat::UrlList => "Liste d'URL",
+        }
+    }
+}
+
+/// Résultat d'un import, à confirmer avant d'ajouter les chaînes
+#[derive(Debug, Default, Serialize)]
+pub struct ImportPreview {
+    /// Chaînes qui seront ajoutées
+    pub channels: Vec<Channel>,
+    /// Chaînes déjà suivies ou présentes plusieurs fois dans le fichier
+    pub duplicates: Vec<Channel>,
+    /// Entrées dont l'URL n'est pas une chaîne d'une plateforme reconnue
+    pub invalid: Vec<String>,
+}
+
+/// Lit un fichier d'abonnements et le compare aux chaînes déjà suivies
+pub fn preview_import(
+    content: &str,
+    format: ImportFormat,
+    existing: &[Channel],
+) -> Result<ImportPreview> {
+    let content = content.trim_start_matches('\u{feff}');
+    let entries = match format {
+        ImportFormat::Opml => parse_opml(content)?,
+        ImportFormat::TakeoutCsv => parse_takeout_csv(content)?,
+        ImportFormat::TwitchFollows => parse_twitch_follows(content)?,
+        ImportFormat::Json => parse_channels(content)?.into_iter().map(Ok).collect(),
+        ImportFormat::UrlList => content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|url| entry(url, None, None))
+            .collect(),
+    };
+
+    let mut preview = ImportPreview::default();
+    for entry in entries {
+        match entry {
+            Ok(channel)
+                if existing
+                    .iter()
+                    .chain(&preview.channels)
+                    .any(|other| other.is_same(&channel)) =>
+            {
+                preview.duplicates.push(channel)
+            }
+            Ok(channel) => preview.channels.push(channel),
+            Err(invalid) => preview.invalid.push(invalid),
+        }
+    }
+    Ok(preview)
+}
+
+/// Ajoute les chaînes importées qui ne sont pas encore suivies, et les renvoie
+pub fn merge_import(channels: &mut Vec<Channel>, imported: Vec<Channel>) -> Vec<Channel> {
+    let mut added = Vec::new();
+    for channel in imported {
+        if !channels.iter().any(|other| other.is_same(&channel)) {
+            channels.push(channel.clone());
+            added.push(channel);
+        }
+    }
+    added
+}
+
+/// Chaîne correspondant à une entrée importée, ou l'entrée telle quelle si
+/// l'URL n'est pas reconnue
+fn entry(url: &str, title: Option<&str>, group: Option<&str>) -> Result<Channel, String> {
+    let mut channel = Channel::from_url(url).ok_or_else(|| match title {
+        Some(title) => format!("{title} ({url})"),
+        None => url.to_string(),
+    })?;
+    channel.display_name = title
+        .map(str::trim)
+        .filter(|title| !title.is_empty() && *title != channel.name)
+        .map(str::to_string);
+    channel.group = group.map(str::to_string);
+    Ok(channel)
+}
+
+/// URL de la chaîne YouTube d'un flux `feeds/videos.xml?channel_id=`
+fn channel_url_from_feed(feed_url: &str) -> Option<String> {
+    let (_, query) = feed_url.split_once("channel_id=")?;
+    let channel_id = query.split('&').next()?;
+    Some(format!("https://www.youtube.com/channel/{channel_id}"))
+}
+
+fn attribute(element: &BytesStart, name: &str) -> Result<Option<String>> {
+    Ok(match element.try_get_attribute(name)? {
+        Some(attribute) => Some(attribute.unescape_value()?.into_owned()),
+        None => None,
+    })
+}
+
+/// Les éléments `outline` sans URL servent de groupes aux éléments qu'ils contiennent
+fn parse_opml(content: &str) -> Result<Vec<Result<Channel, String>>> {
+    let mut reader = Reader::from_str(content);
+    let mut entries = Vec::new();
+    // Groupe de chaque `outline` ouvert (None pour une chaîne)
+    let mut groups: Vec<Option<String>> = Vec::new();
+
+    loop {
+        let event = reader.read_event().context("OPML invalide")?;
+        let (element, has_children) = match &event {
+            Event::Start(element) => (element, true),
+            Event::Empty(element) => (element, false),
+            Event::End(element) if element.name().as_ref() == b"outline" => {
+                groups.pop();
+                continue;
+            }
+            Event::Eof => break,
+            _ => continue,
+        };
+        if element.name().as_ref() != b"outline" {
+            continue;
+        }
+
+        let title = attribute(element, "title")?.or(attribute(element, "text")?);
+        let url = attribute(element, "xmlUrl")?
+            .and_then(|feed| channel_url_from_feed(&feed).or(Some(feed)))
+            .or(attribute(element, "htmlUrl")?);
+        match url {
+            Some(url) => {
+                let group = groups.iter().rev().flatten().next();
+                entries.push(entry(&url, title.as_deref(), group.map(String::as_str)));
+                if has_children {
+                    groups.push(None);
+                }
+            }
+            None if has_children => groups.push(title),
+            None => {}
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Colonnes : identifiant, URL et nom de la chaîne (les en-têtes sont traduits)
+fn parse_takeout_csv(content: &str) -> Result<Vec<Result<Channel, String>>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(content.as_bytes());
+    let mut entries = Vec::new();
+    for record in reader.records() {
+        let record = record.context("CSV invalide")?;
+        let channel_id = record.get(0).unwrap_or_default().trim();
+        let title = record.get(2).map(str::trim);
+        let url = match record.get(1).map(str::trim) {
+            Some(url) if !url.is_empty() => url.replacen("http://", "https://", 1),
+            _ if !channel_id.is_empty() => {
+                format!("https://www.youtube.com/channel/{channel_id}")
+            }
+            _ => continue,
+        };
+        entries.push(entry(&url, title, None));
+    }
+    Ok(entries)
+}
+
+/// Entrée de `channels/followed` (ou de l'ancien `users/follows`)
+#[derive(Deserialize)]
+struct TwitchFollow {
+    #[serde(alias = "to_login")]
+    broadcaster_login: String,
+    #[serde(default, alias = "to_name")]
+    broadcaster_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TwitchFollows {
+    Page { data: Vec<TwitchFollow> },
+    List(Vec<TwitchFollow>),
+}
+
+fn parse_twitch_follows(content: &str) -> Result<Vec<Result<Channel, String>>> {
+    let follows =
+        match serde_json::from_str(content).context("Liste des chaînes suivies Twitch invalide")? {
+            TwitchFollows::Page { data } => data,
+            TwitchFollows::List(follows) => follows,
+        };
+    Ok(follows
+        .iter()
+        .map(|follow| {
+            entry(
+                &format!("https://www.twitch.tv/{}", follow.broadcaster_login),
+                follow.broadcaster_name.as_deref(),
+                None,
+            )
+        })
+        .collect())
+}
+
+/// Formats d'export de la liste des chaînes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Réglages compris, réimportable tel quel
+    Json,
+    /// Pour les lecteurs RSS (les chaînes Twitch n'ont pas de flux)
+    Opml,
+}
+
+impl ExportFormat {
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension)
+                if extension.eq_ignore_ascii_case("opml")
+                    || extension.eq_ignore_ascii_case("xml") =>
+            {
+                ExportFormat::Opml
+            }
+            _ => ExportFormat::Json,
+        }
+    }
+}
+
+pub fn export_channels(channels: &[Channel], format: ExportFormat) -> Result<String> {
+    match format {
+        ExportFormat::Json => channels_to_json(channels),
+        ExportFormat::Opml => Ok(export_opml(channels)),
+    }
+}
+
+fn opml_outline(channel: &Channel) -> String {
+    let title = escape(channel.display_name());
+    let url = escape(&channel.url);
+    // Identifiant de l'URL, ou celui résolu pour une chaîne ajoutée par @handle
+    let channel_id = channel
+        .url
+        .split_once("/channel/")
+        .and_then(|(_, rest)| rest.split(['/', '?']).next())
+        .or(channel.channel_id.as_deref());
+    let feed = match (&channel.platform, channel_id) {
+        (Platform::YouTube, Some(channel_id)) => format!(
+            " type=\"rss\" xmlUrl=\"https://www.youtube.com/feeds/videos.xml?channel_id={}\"",
+            escape(channel_id)
+        ),
+        _ => String::new(),
+    };
+    format!("<outline text=\"{title}\" title=\"{title}\"{feed} htmlUrl=\"{url}\"/>")
+}
+
+/// Les groupes deviennent des `outline` contenant leurs chaînes
+fn export_opml(channels: &[Channel]) -> String {
+    let mut body = String::new();
+    for group in crate::channels::group_channels(channels, None) {
+        let indent = match &group.name {
+            Some(name) => {
+                body.push_str(&format!("    <outline text=\"{}\">\n", escape(name)));
+                "      "
+            }
+            None => "    ",
+        };
+        for index in group.channels {
+            body.push_str(&format!("{indent}{}\n", opml_outline(&channels[index])));
+        }
+        if group.name.is_some() {
+            body.push_str("    </outline>\n");
+        }
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <opml version=\"1.1\">\n  \
+         <head>\n    <title>Chaînes NDownloader</title>\n  </head>\n  \
+         <body>\n{body}  </body>\n\
+         </opml>\n"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_format() {
+        let path = Path::new("abonnements");
+        assert_eq!(
+            ImportFormat::detect(Path::new("a.opml"), ""),
+            ImportFormat::Opml
+        );
+        assert_eq!(
+            ImportFormat::detect(path, "Channel Id,Channel Url,Channel Title\n"),
+            ImportFormat::TakeoutCsv
+        );
+        assert_eq!(
+            ImportFormat::detect(path, r#"{"data": [{"broadcaster_login": "a"}]}"#),
+            ImportFormat::TwitchFollows
+        );
+        assert_eq!(
+            ImportFormat::detect(path, r#"{"version": 1, "channels": []}"#),
+            ImportFormat::Json
+        );
+        assert_eq!(
+            ImportFormat::detect(path, "https://twitch.tv/a\n"),
+            ImportFormat::UrlList
+        );
+    }
+
+    #[test]
+    fn test_preview_reports_duplicates_and_invalid() {
+        let existing = vec![Channel::from_url("https://www.twitch.tv/suivie").unwrap()];
+        let content = "# abonnements\n\
+                       https://twitch.tv/Suivie\n\
+                       https://twitch.tv/nouvelle\n\
+                       https://twitch.tv/nouvelle/\n\
+                       https://example.com/chaine\n";
+
+        let preview = preview_import(content, ImportFormat::UrlList, &existing).unwrap();
+        let names: Vec<&str> = preview.channels.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["nouvelle"]);
+        assert_eq!(preview.duplicates.len(), 2);
+        assert_eq!(preview.invalid, ["https://example.com/chaine"]);
+    }
+
+    #[test]
+    fn test_import_takeout_csv() {
+        let content = "Channel Id,Channel Url,Channel Title\n\
+                       UCabc,http://www.youtube.com/channel/UCabc,Une Chaîne\n\
+                       UCdef,,Sans URL\n";
+
+        let preview = preview_import(content, ImportFormat::TakeoutCsv, &[]).unwrap();
+        assert_eq!(preview.channels.len(), 2);
+        assert_eq!(
+            preview.channels[0].url,
+            "https://www.youtube.com/channel/UCabc"
+        );
+        assert_eq!(preview.channels[0].display_name(), "Une Chaîne");
+        assert_eq!(preview.channels[1].name, "UCdef");
+    }
+
+    #[test]
+    fn test_import_twitch_follows() {
+        let content =
+            r#"{"data": [{"broadcaster_login": "chaine", "broadcaster_name": "Chaine"}]}"#;
+        let preview = preview_import(content, ImportFormat::TwitchFollows, &[]).unwrap();
+        assert_eq!(preview.channels[0].url, "https://www.twitch.tv/chaine");
+        assert_eq!(preview.channels[0].display_name(), "Chaine");
+    }
+
+    #[test]
+    fn test_opml_round_trip_keeps_groups() {
+        let mut channels: Vec<Channel> = [
+            "https://www.youtube.com/channel/UCabc",
+            "https://www.twitch.tv/chaine",
+            "https://www.youtube.com/@handle",
+        ]
+        .into_iter()
+        .filter_map(Channel::from_url)
+        .collect();
+        channels[0].display_name = Some("Science & Co".to_string());
+        channels[0].group = Some("Vulgarisation".to_string());
+        channels[2].channel_id = Some("UCresolu".to_string());
+
+        let opml = export_channels(&channels, ExportFormat::Opml).unwrap();
+        assert!(opml.contains("feeds/videos.xml?channel_id=UCabc"));
+        assert!(opml.contains("feeds/videos.xml?channel_id=UCresolu"));
+        assert!(opml.contains("Science &amp; Co"));
+
+        let preview = preview_import(&opml, ImportFormat::Opml, &[]).unwrap();
+        assert!(preview.invalid.is_empty());
+        assert_eq!(preview.channels.len(), 3);
+        assert_eq!(preview.channels[0].url, channels[0].url);
+        assert_eq!(preview.channels[0].display_name(), "Science & Co");
+        assert_eq!(preview.channels[0].group.as_deref(), Some("Vulgarisation"));
+        assert_eq!(preview.channels[1].group, None);
+    }
+
+    #[test]
+    fn test_json_export_keeps_settings() {
+        let mut channel = Channel::from_url("https://www.twitch.tv/chaine").unwrap();
+        channel.tags = vec!["fr".to_string()];
+        channel.monitor.enabled = true;
+
+        let json = export_channels(&[channel], ExportFormat::Json).unwrap();
+        let preview = preview_import(&json, ImportFormat::Json, &[]).unwrap();
+        assert_eq!(preview.channels[0].tags, ["fr"]);
+        assert!(preview.channels[0].monitor.enabled);
+    }
+}
@@ -0,0 +1,507 @@
+use super::settings::prefilled_input;
+use super::*;
+use crate::downloader_queue::FormatProfile;
+use chrono::NaiveDate;
+use std::path::PathBuf;
+
+/// Boîte de dialogue d'édition d'une chaîne
+pub(super) struct ChannelEditor {
+    /// URL de la chaîne éditée (l'index peut changer pendant l'édition)
+    url: String,
+    display_name_input: Entity<TextInputView>,
+    folder_input: Entity<TextInputView>,
+    storage_root_input: Entity<TextInputView>,
+    template_input: Entity<TextInputView>,
+    group_input: Entity<TextInputView>,
+    tags_input: Entity<TextInputView>,
+    interval_input: Entity<TextInputView>,
+    include_input: Entity<TextInputView>,
+    exclude_input: Entity<TextInputView>,
+    min_duration_input: Entity<TextInputView>,
+    max_duration_input: Entity<TextInputView>,
+    after_input: Entity<TextInputView>,
+    before_input: Entity<TextInputView>,
+    max_age_input: Entity<TextInputView>,
+    format: FormatProfile,
+    monitor_enabled: bool,
+    monitor_mode: MonitorMode,
+    backfill: bool,
+    only_vods: bool,
+    skip_live: bool,
+    last_scan: Option<String>,
+    error: Option<String>,
+}
+
+fn format_label(format: FormatProfile) -> &'static str {
+    match format {
+        FormatProfile::Best => "Meilleure qualité",
+        FormatProfile::Max1080p => "1080p maximum",
+        FormatProfile::Max720p => "720p maximum",
+        FormatProfile::Max480p => "480p maximum",
+        FormatProfile::AudioOnly => "Audio seulement",
+    }
+}
+
+fn next_format(format: FormatProfile) -> FormatProfile {
+    match format {
+        FormatProfile::Best => FormatProfile::Max1080p,
+        FormatProfile::Max1080p => FormatProfile::Max720p,
+        FormatProfile::Max720p => FormatProfile::Max480p,
+        FormatProfile::Max480p => FormatProfile::AudioOnly,
+        FormatProfile::AudioOnly => FormatProfile::Best,
+    }
+}
+
+fn optional_text(value: Option<impl ToString>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+fn parse_optional<T: std::str::FromStr>(
+    value: Option<String>,
+    error: &str,
+) -> Result<Option<T>, String> {
+    value
+        .map(|value| value.parse().map_err(|_| error.to_string()))
+        .transpose()
+}
+
+fn editor_mut(app: &mut NDownloaderApp) -> Option<&mut ChannelEditor> {
+    app.channel_editor.as_mut()
+}
+
+/// Liste saisie sur une ligne, éléments séparés par `separator`
+fn split_list(text: &str, separator: char) -> Vec<String> {
+    text.split(separator)
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+impl ChannelEditor {
+    fn new(channel: &Channel, cx: &mut App) -> Self {
+        let rules = &channel.rules;
+        Self {
+            url: channel.url.clone(),
+            display_name_input: prefilled_input(cx, channel.display_name().to_string()),
+            folder_input: prefilled_input(cx, channel.name.clone()),
+            storage_root_input: prefilled_input(
+                cx,
+                optional_text(channel.storage_root.as_ref().map(|path| path.display())),
+            ),
+            template_input: prefilled_input(cx, optional_text(channel.filename_template.as_ref())),
+            group_input: prefilled_input(cx, optional_text(channel.group.as_ref())),
+            tags_input: prefilled_input(cx, channel.tags.join(", ")),
+            interval_input: prefilled_input(cx, channel.monitor.interval_minutes.to_string()),
+            include_input: prefilled_input(cx, rules.title_include.join(" ; ")),
+            exclude_input: prefilled_input(cx, rules.title_exclude.join(" ; ")),
+            min_duration_input: prefilled_input(cx, optional_text(rules.min_duration_secs)),
+            max_duration_input: prefilled_input(cx, optional_text(rules.max_duration_secs)),
+            after_input: prefilled_input(cx, optional_text(rules.uploaded_after)),
+            before_input: prefilled_input(cx, optional_text(rules.uploaded_before)),
+            max_age_input: prefilled_input(cx, optional_text(rules.max_age_days)),
+            format: channel.format,
+            monitor_enabled: channel.monitor.enabled,
+            monitor_mode: channel.monitor.mode,
+            backfill: channel.backfill,
+            only_vods: rules.only_vods,
+            skip_live: rules.skip_live,
+            last_scan: channel.last_scan.map(|date| {
+                date.with_timezone(&chrono::Local)
+                    .format("%d/%m/%Y %H:%M")
+                    .to_string()
+            }),
+            error: None,
+        }
+    }
+
+    /// Applique la saisie à la chaîne (sans l'enregistrer)
+    fn to_channel(&self, current: &Channel, cx: &App) -> Result<Channel, String> {
+        let read = |input: &Entity<TextInputView>| input.read(cx).value().trim().to_string();
+        let optional = |input: &Entity<TextInputView>| Some(read(input)).filter(|v| !v.is_empty());
+        let parse_date = |input: &Entity<TextInputView>, field: &str| {
+            optional(input)
+                .map(|value| {
+                    NaiveDate::parse_from_str(&value, "%Y-%m-%d")
+                        .map_err(|_| format!("{field} doit être au format AAAA-MM-JJ"))
+                })
+                .transpose()
+        };
+
+        let folder = read(&self.folder_input);
+        if folder.is_empty() || folder.contains(['/', '\\']) || folder == ".." {
+            return Err("Le nom du dossier est invalide".to_string());
+        }
+
+        let mut channel = current.clone();
+        channel.display_name = optional(&self.display_name_input).filter(|name| *name != folder);
+        channel.name = folder;
+        channel.storage_root = optional(&self.storage_root_input).map(PathBuf::from);
+        channel.filename_template = optional(&self.template_input);
+        channel.format = self.format;
+        channel.group = optional(&self.group_input);
+        channel.tags = split_list(&read(&self.tags_input), ',');
+
+        channel.monitor.enabled = self.monitor_enabled;
+        channel.monitor.mode = self.monitor_mode;
+        channel.backfill = self.backfill;
+        channel.monitor.interval_minutes = parse_optional(
+            optional(&self.interval_input),
+            "L'intervalle doit être un nombre de minutes",
+        )?
+        .unwrap_or(channel.monitor.interval_minutes);
+
+        let rules = &mut channel.rules;
+        rules.title_include = split_list(&read(&self.include_input), ';');
+        rules.title_exclude = split_list(&read(&self.exclude_input), ';');
+        rules.min_duration_secs = parse_optional(
+            optional(&self.min_duration_input),
+            "La durée minimale doit être un nombre de secondes",
+        )?;
+        rules.max_duration_secs = parse_optional(
+            optional(&self.max_duration_input),
+            "La durée maximale doit être un nombre de secondes",
+        )?;
+        rules.uploaded_after = parse_date(&self.after_input, "« Publiée après »")?;
+        rules.uploaded_before = parse_date(&self.before_input, "« Publiée avant »")?;
+        rules.max_age_days = parse_optional(
+            optional(&self.max_age_input),
+            "L'ancienneté maximale doit être un nombre de jours",
+        )?;
+        rules.only_vods = self.only_vods;
+        rules.skip_live = self.skip_live;
+        rules.compile().map_err(|error| error.to_string())?;
+
+        Ok(channel)
+    }
+}
+
+impl NDownloaderApp {
+    pub(super) fn open_channel_editor(&mut self, index: usize, cx: &mut Context<Self>) {
+        let Some(channel) = self.channels.get(index) else {
+            return;
+        };
+        self.channel_editor = Some(ChannelEditor::new(channel, cx));
+        cx.notify();
+    }
+
+    pub(super) fn close_channel_editor(&mut self, cx: &mut Context<Self>) {
+        self.channel_editor = None;
+        cx.notify();
+    }
+
+    fn save_channel_editor(&mut self, cx: &mut Context<Self>) {
+        let Some(editor) = &mut self.channel_editor else {
+            return;
+        };
+        let Some(index) = self.channels.iter().position(|c| c.url == editor.url) else {
+            self.channel_editor = None;
+            cx.notify();
+            return;
+        };
+
+        match editor.to_channel(&self.channels[index], cx) {
+            Ok(channel) => {
+                self.channels[index] = channel;
+                self.channel_editor = None;
+                self.save_channels();
+            }
+            Err(error) => {
+                tracing::warn!("Invalid channel settings: {}", error);
+                editor.error = Some(error);
+            }
+        }
+        cx.notify();
+    }
+
+    pub(super) fn render_channel_editor(
+        &mut self,
+        main_content: AnyElement,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let Some(editor) = &self.channel_editor else {
+            return main_content;
+        };
+
+        let section_title = |title: &'static str| {
+            div()
+                .text_color(rgb(NORD6))
+                .text_size(px(15.0))
+                .font_weight(FontWeight::SEMIBOLD)
+                .child(title)
+        };
+        let labeled = |label: &'static str, content: AnyElement| {
+            div()
+                .flex()
+                .items_center()
+                .gap_3()
+                .child(
+                    div()
+                        .w(px(200.0))
+                        .text_color(rgb(NORD4))
+                        .text_size(px(13.0))
+                        .child(label),
+                )
+                .child(content)
+        };
+        let input_box = |input: Entity<TextInputView>| {
+            div()
+                .flex_1()
+                .h_10()
+                .px_3()
+                .bg(rgb(NORD2))
+                .border_1()
+                .border_color(rgb(NORD3))
+                .rounded_md()
+                .child(input)
+                .into_any_element()
+        };
+        let button = |label: String, color: u32| {
+            div()
+                .px_3()
+                .py_1()
+                .bg(rgb(color))
+                .rounded_md()
+                .cursor_pointer()
+                .hover(|style| style.bg(rgb(NORD3)))
+                .child(
+                    div()
+                        .text_color(rgb(NORD6))
+                        .text_size(px(13.0))
+                        .font_weight(FontWeight::SEMIBOLD)
+                        .child(label),
+                )
+        };
+        let toggle = |enabled: bool| {
+            button(
+                if enabled { "Oui" } else { "Non" }.to_string(),
+                if enabled { NORD14 } else { NORD3 },
+            )
+        };
+
+        let monitor_label = match (editor.monitor_enabled, editor.monitor_mode) {
+            (false, _) => "Désactivée",
+            (true, MonitorMode::NotifyOnly) => "Notification",
+            (true, MonitorMode::AutoDownload) => "Téléchargement automatique",
+        };
+
+        let dialog = div()
+            .id("channel-editor")
+            .w(px(640.0))
+            .max_h(relative(0.9))
+            .overflow_y_scroll()
+            .bg(rgb(NORD1))
+            .rounded_lg()
+            .p_6()
+            .flex()
+            .flex_col()
+            .gap_3()
+            .on_mouse_down(MouseButton::Left, |_event, _phase, cx| {
+                cx.stop_propagation();
+            })
+            .child(
+                div()
+                    .text_color(rgb(NORD6))
+                    .text_size(px(18.0))
+                    .font_weight(FontWeight::BOLD)
+                    .child("Modifier la chaîne"),
+            )
+            .child(div().text_color(rgb(NORD4)).text_size(px(12.0)).child(
+                match &editor.last_scan {
+                    Some(date) => format!("Dernier scan : {date}"),
+                    None => "Jamais scannée".to_string(),
+                },
+            ))
+            .child(section_title("Général"))
+            .child(labeled(
+                "Nom affiché",
+                input_box(editor.display_name_input.clone()),
+            ))
+            .child(labeled(
+                "Dossier sur le disque",
+                input_box(editor.folder_input.clone()),
+            ))
+            .child(labeled("Groupe", input_box(editor.group_input.clone())))
+            .child(labeled(
+                "Tags (séparés par ,)",
+                input_box(editor.tags_input.clone()),
+            ))
+            .child(section_title("Téléchargement"))
+            .child(labeled(
+                "Emplacement préféré",
+                input_box(editor.storage_root_input.clone()),
+            ))
+            .child(labeled(
+                "Modèle de nom de fichier",
+                input_box(editor.template_input.clone()),
+            ))
+            .child(
+                div()
+                    .text_color(rgb(NORD4))
+                    .text_size(px(12.0))
+                    .child("Variables : {title}, {id}, {date}, {uploader}, {platform}"),
+            )
+            .child(labeled(
+                "Qualité",
+                button(format_label(editor.format).to_string(), NORD10)
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event, _window, cx| {
+                            if let Some(editor) = editor_mut(this) {
+                                editor.format = next_format(editor.format);
+                                cx.notify();
+                            }
+                        }),
+                    )
+                    .into_any_element(),
+            ))
+            .child(section_title("Surveillance"))
+            .child(labeled(
+                "Mode",
+                button(monitor_label.to_string(), NORD10)
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event, _window, cx| {
+                            if let Some(editor) = editor_mut(this) {
+                                match (editor.monitor_enabled, editor.monitor_mode) {
+                                    (false, _) => {
+                                        editor.monitor_enabled = true;
+                                        editor.monitor_mode = MonitorMode::NotifyOnly;
+                                    }
+                                    (true, MonitorMode::NotifyOnly) => {
+                                        editor.monitor_mode = MonitorMode::AutoDownload
+                                    }
+                                    (true, MonitorMode::AutoDownload) => {
+                                        editor.monitor_enabled = false
+                                    }
+                                }
+                                cx.notify();
+                            }
+                        }),
+                    )
+                    .into_any_element(),
+            ))
+            .child(labeled(
+                "Intervalle (minutes)",
+                input_box(editor.interval_input.clone()),
+            ))
+            .child(labeled(
+                "Tout l'historique au 1er scan",
+                toggle(editor.backfill)
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event, _window, cx| {
+                            if let Some(editor) = editor_mut(this) {
+                                editor.backfill = !editor.backfill;
+                                cx.notify();
+                            }
+                        }),
+                    )
+                    .into_any_element(),
+            ))
+            .child(section_title("Règles"))
+            .child(labeled(
+                "Titre contient (regex, ;)",
+                input_box(editor.include_input.clone()),
+            ))
+            .child(labeled(
+                "Titre exclu (regex, ;)",
+                input_box(editor.exclude_input.clone()),
+            ))
+            .child(labeled(
+                "Durée minimale (s)",
+                input_box(editor.min_duration_input.clone()),
+            ))
+            .child(labeled(
+                "Durée maximale (s)",
+                input_box(editor.max_duration_input.clone()),
+            ))
+            .child(labeled(
+                "Publiée après (AAAA-MM-JJ)",
+                input_box(editor.after_input.clone()),
+            ))
+            .child(labeled(
+                "Publiée avant (AAAA-MM-JJ)",
+                input_box(editor.before_input.clone()),
+            ))
+            .child(labeled(
+                "Ancienneté max. (jours)",
+                input_box(editor.max_age_input.clone()),
+            ))
+            .child(labeled(
+                "Uniquement les VODs",
+                toggle(editor.only_vods)
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event, _window, cx| {
+                            if let Some(editor) = editor_mut(this) {
+                                editor.only_vods = !editor.only_vods;
+                                cx.notify();
+                            }
+                        }),
+                    )
+                    .into_any_element(),
+            ))
+            .child(labeled(
+                "Ignorer les directs",
+                toggle(editor.skip_live)
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event, _window, cx| {
+                            if let Some(editor) = editor_mut(this) {
+                                editor.skip_live = !editor.skip_live;
+                                cx.notify();
+                            }
+                        }),
+                    )
+                    .into_any_element(),
+            ))
+            .when_some(editor.error.clone(), |this, error| {
+                this.child(
+                    div()
+                        .p_3()
+                        .bg(rgb(NORD11))
+                        .rounded_md()
+                        .text_color(rgb(NORD6))
+                        .text_size(px(13.0))
+                        .child(error),
+                )
+            })
+            .child(
+                div()
+                    .flex()
+                    .justify_end()
+                    .gap_2()
+                    .child(button("Annuler".to_string(), NORD3).on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| this.close_channel_editor(cx)),
+                    ))
+                    .child(button("Enregistrer".to_string(), NORD10).on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| this.save_channel_editor(cx)),
+                    )),
+            );
+
+        div()
+            .size_full()
+            .relative()
+            .child(main_content)
+            .child(
+                div()
+                    .absolute()
+                    .top_0()
+                    .left_0()
+                    .size_full()
+                    .bg(black().opacity(0.7))
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| this.close_channel_editor(cx)),
+                    )
+                    .child(dialog),
+            )
+            .into_any_element()
+    }
+}
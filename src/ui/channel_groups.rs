@@ -0,0 +1,271 @@
+use super::*;
+use crate::channels::{toggle_monitoring_pause, ChannelGroup};
+
+impl NDownloaderApp {
+    fn group_indices(&self, group: &Option<String>) -> Vec<usize> {
+        self.channels
+            .iter()
+            .enumerate()
+            .filter(|(_, channel)| channel.group == *group)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    fn toggle_group(&mut self, group: Option<String>, cx: &mut Context<Self>) {
+        if !self.collapsed_groups.remove(&group) {
+            self.collapsed_groups.insert(group);
+        }
+        cx.notify();
+    }
+
+    fn set_tag_filter(&mut self, tag: Option<String>, cx: &mut Context<Self>) {
+        self.tag_filter = tag;
+        cx.notify();
+    }
+
+    /// Suspend la surveillance du groupe, ou la reprend si elle est déjà suspendue
+    fn toggle_group_monitoring(&mut self, group: Option<String>, cx: &mut Context<Self>) {
+        let indices = self.group_indices(&group);
+        toggle_monitoring_pause(&mut self.channels, &indices);
+        self.save_channels();
+        cx.notify();
+    }
+
+    /// Scanne l'une après l'autre toutes les chaînes du groupe
+    fn scan_group(&mut self, group: Option<String>, cx: &mut Context<Self>) {
+        if !self.scanning_groups.insert(group.clone()) {
+            return;
+        }
+        let channels: Vec<Channel> = self
+            .group_indices(&group)
+            .into_iter()
+            .map(|index| self.channels[index].clone())
+            .collect();
+        let scanner = self.scanner.clone();
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let mut total = 0;
+            let mut failed = 0;
+            for channel in channels {
+                let result = scanner
+                    .scan_channel(&channel, ScanMode::first_page(), false)
+                    .await;
+                let updated = this.update(cx, |this, cx| {
+                    match result {
+                        Ok(videos) => {
+                            let tasks = this.download_queue.get_tasks();
+                            let count = videos
+                                .iter()
+                                .filter(|scanned| scanned.is_new())
+                                .filter(|scanned| {
+                                    !tasks.iter().any(|task| task.video_url == scanned.video.url)
+                                })
+                                .count();
+                            total += count;
+                            this.new_videos.insert(channel.url.clone(), count);
+                            this.record_scan_date(&channel.url);
+                        }
+                        Err(error) => {
+                            tracing::warn!("Failed to scan {}: {}", channel.name, error);
+                            failed += 1;
+                        }
+                    }
+                    cx.notify();
+                });
+                if updated.is_err() {
+                    return;
+                }
+            }
+
+            this.update(cx, |this, cx| {
+                this.scanning_groups.remove(&group);
+                cx.notify();
+            })
+            .ok();
+
+            let group_name = group.as_deref().unwrap_or("Sans groupe");
+            if failed > 0 {
+                Notification::error(
+                    &format!("Scan du groupe {group_name}"),
+                    &format!("{failed} chaîne(s) n'ont pas pu être scannées"),
+                );
+            }
+            Notification::info(
+                &format!("Scan du groupe {group_name}"),
+                &format!("{total} nouvelle(s) vidéo(s)"),
+            );
+        })
+        .detach();
+    }
+
+    pub(super) fn render_tag_filter(&self, tags: Vec<String>, cx: &mut Context<Self>) -> Div {
+        let chip = |label: String, active: bool| {
+            div()
+                .px_2()
+                .py_1()
+                .bg(rgb(if active { NORD10 } else { NORD2 }))
+                .rounded_sm()
+                .cursor_pointer()
+                .hover(|style| style.bg(rgb(NORD3)))
+                .child(
+                    div()
+                        .text_color(rgb(NORD6))
+                        .text_size(px(12.0))
+                        .child(label),
+                )
+        };
+
+        div()
+            .flex()
+            .flex_wrap()
+            .gap_2()
+            .child(
+                chip("Tous".to_string(), self.tag_filter.is_none()).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, cx| this.set_tag_filter(None, cx)),
+                ),
+            )
+            .children(tags.into_iter().map(|tag| {
+                let active = self.tag_filter.as_ref() == Some(&tag);
+                chip(format!("#{tag}"), active).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _event, _window, cx| {
+                        let tag = (!active).then(|| tag.clone());
+                        this.set_tag_filter(tag, cx);
+                    }),
+                )
+            }))
+    }
+
+    pub(super) fn render_channel_group(
+        &self,
+        group: ChannelGroup,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let rows = |cx: &mut Context<Self>| -> Vec<Div> {
+            group
+                .channels
+                .iter()
+                .map(|&index| self.render_channel_row(index, cx))
+                .collect()
+        };
+
+        // Sans aucun groupe défini, la liste reste à plat
+        if group.name.is_none() && self.channels.iter().all(|c| c.group.is_none()) {
+            return div()
+                .flex()
+                .flex_col()
+                .gap_2()
+                .children(rows(cx))
+                .into_any_element();
+        }
+
+        let collapsed = self.collapsed_groups.contains(&group.name);
+        let scanning = self.scanning_groups.contains(&group.name);
+        let monitoring = group
+            .channels
+            .iter()
+            .any(|&index| self.channels[index].monitor.is_active());
+        let new_count: usize = group
+            .channels
+            .iter()
+            .filter_map(|&index| self.new_videos.get(&self.channels[index].url))
+            .sum();
+        let action = |label: &'static str| {
+            div()
+                .px_2()
+                .py_1()
+                .bg(rgb(NORD2))
+                .rounded_sm()
+                .cursor_pointer()
+                .hover(|style| style.bg(rgb(NORD3)))
+                .child(
+                    div()
+                        .text_color(rgb(NORD6))
+                        .text_size(px(12.0))
+                        .child(label),
+                )
+        };
+
+        let toggle_name = group.name.clone();
+        let scan_name = group.name.clone();
+        let monitor_name = group.name.clone();
+        let header = div()
+            .flex()
+            .items_center()
+            .gap_2()
+            .py_1()
+            .child(
+                div()
+                    .flex_1()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .cursor_pointer()
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event, _window, cx| {
+                            this.toggle_group(toggle_name.clone(), cx);
+                        }),
+                    )
+                    .child(
+                        div()
+                            .text_color(rgb(NORD6))
+                            .text_size(px(14.0))
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .child(format!(
+                                "{} {} ({})",
+                                if collapsed { "▸" } else { "▾" },
+                                group.name.as_deref().unwrap_or("Sans groupe"),
+                                group.channels.len()
+                            )),
+                    )
+                    .when(new_count > 0, |this| {
+                        this.child(
+                            div().px_2().bg(rgb(NORD13)).rounded_sm().child(
+                                div()
+                                    .text_color(rgb(NORD0))
+                                    .text_size(px(12.0))
+                                    .font_weight(FontWeight::BOLD)
+                                    .child(format!("{new_count} nouvelle(s)")),
+                            ),
+                        )
+                    }),
+            )
+            .child(
+                action(if scanning {
+                    "Scan en cours..."
+                } else {
+                    "Tout scanner"
+                })
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _event, _window, cx| {
+                        this.scan_group(scan_name.clone(), cx);
+                    }),
+                ),
+            )
+            .child(
+                action(if monitoring {
+                    "Suspendre la surveillance"
+                } else {
+                    "Reprendre la surveillance"
+                })
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _event, _window, cx| {
+                        this.toggle_group_monitoring(monitor_name.clone(), cx);
+                    }),
+                ),
+            );
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(header)
+            .when(!collapsed, |this| this.children(rows(cx)))
+            .into_any_element()
+    }
+}
@@ -1,6 +1,21 @@
 use gpui::*;
 
-use super::super::{Channel, Platform, NORD11, NORD15, NORD6};
+use super::super::{NORD10, NORD11, NORD12, NORD13, NORD14, NORD15, NORD3, NORD4, NORD6, NORD8};
+use crate::channels::{Channel, Platform};
+
+/// Couleur du badge de la plateforme
+pub fn platform_color(platform: &Platform) -> Rgba {
+    match platform {
+        Platform::YouTube => rgb(NORD11),
+        Platform::Twitch => rgb(NORD15),
+        Platform::Kick => rgb(NORD14),
+        Platform::Vimeo => rgb(NORD8),
+        Platform::PeerTube => rgb(NORD12),
+        Platform::Dailymotion => rgb(NORD10),
+        Platform::Odysee => rgb(NORD13),
+        Platform::Other => rgb(NORD3),
+    }
+}
 
 #[derive(IntoElement)]
 pub struct ChannelItem {
@@ -15,14 +30,8 @@ impl ChannelItem {
 
 impl RenderOnce for ChannelItem {
     fn render(self, _window: &mut Window, _cx: &mut App) -> impl IntoElement {
-        let platform_color = match self.channel.platform {
-            Platform::YouTube => rgb(NORD11),
-            Platform::Twitch => rgb(NORD15),
-        };
-        let platform_name = match self.channel.platform {
-            Platform::YouTube => "YouTube",
-            Platform::Twitch => "Twitch",
-        };
+        let platform_color = platform_color(&self.channel.platform);
+        let platform_name = self.channel.platform.extractor().label();
 
         div()
             .flex()
@@ -41,7 +50,13 @@ impl RenderOnce for ChannelItem {
                 div()
                     .text_color(rgb(NORD6))
                     .text_size(px(14.0))
-                    .child(self.channel.name.clone()),
+                    .child(self.channel.display_name().to_string()),
             )
+            .children(self.channel.tags.iter().map(|tag| {
+                div()
+                    .text_color(rgb(NORD4))
+                    .text_size(px(12.0))
+                    .child(format!("#{tag}"))
+            }))
     }
 }
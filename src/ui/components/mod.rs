@@ -2,6 +2,6 @@ mod channel_item;
 mod progress_bar;
 mod video_item;
 
-pub use channel_item::ChannelItem;
+pub use channel_item::{platform_color, ChannelItem};
 pub use progress_bar::ProgressBar;
 pub use video_item::VideoItem;
@@ -1,13 +1,15 @@
 use gpui::prelude::FluentBuilder;
 use gpui::*;
 
-use super::super::{VideoInfo, VideoStatus, NORD13, NORD14, NORD6, NORD9};
+use super::super::{VideoInfo, VideoStatus, NORD13, NORD14, NORD15, NORD2, NORD4, NORD6, NORD9};
 use super::ProgressBar;
+use crate::download_progress::{format_eta, format_size};
 
 #[derive(IntoElement)]
 pub struct VideoItem {
     video: VideoInfo,
     progress: Option<f32>,
+    details: Option<String>,
 }
 
 impl VideoItem {
@@ -15,6 +17,7 @@ impl VideoItem {
         Self {
             video,
             progress: None,
+            details: None,
         }
     }
 
@@ -22,6 +25,12 @@ impl VideoItem {
         self.progress = Some(progress);
         self
     }
+
+    /// Détails affichés sous la barre de progression (vitesse, ETA)
+    pub fn with_details(mut self, details: impl Into<String>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
 }
 
 impl RenderOnce for VideoItem {
@@ -29,14 +38,46 @@ impl RenderOnce for VideoItem {
         let (indicator_color, status_text, status_color) = match self.video.status {
             VideoStatus::Downloaded => (rgb(NORD14), "Téléchargé", rgb(NORD14)),
             VideoStatus::Downloading => (rgb(NORD9), "En cours...", rgb(NORD9)),
+            VideoStatus::Merging => (rgb(NORD9), "Fusion audio/vidéo...", rgb(NORD9)),
+            VideoStatus::Paused => (rgb(NORD15), "En pause", rgb(NORD15)),
             VideoStatus::NotDownloaded => (rgb(NORD13), "Non téléchargé", rgb(NORD13)),
         };
 
+        let rejection = self.video.rejection.clone();
+
+        // Durée, taille et chapitres, connus au fur et à mesure du chargement des détails
+        let mut facts = Vec::new();
+        if let Some(duration) = self.video.duration {
+            facts.push(format_eta(duration as u64));
+        }
+        if let Some(size) = self.video.filesize_approx {
+            facts.push(format_size(size));
+        }
+        if self.video.chapters > 0 {
+            facts.push(format!("{} chapitre(s)", self.video.chapters));
+        }
+        if !self.video.enriched {
+            facts.push("chargement des détails...".to_string());
+        }
+
         div()
             .flex()
             .items_center()
             .gap_3()
+            .when(rejection.is_some(), |this| this.opacity(0.45))
             .child(div().w_3().h_3().rounded_full().bg(indicator_color))
+            .child(
+                div()
+                    .w(px(96.0))
+                    .h(px(54.0))
+                    .flex_none()
+                    .rounded_sm()
+                    .overflow_hidden()
+                    .bg(rgb(NORD2))
+                    .when_some(self.video.thumbnail.clone(), |this, thumbnail| {
+                        this.child(img(thumbnail).size_full().object_fit(ObjectFit::Cover))
+                    }),
+            )
             .child(
                 div()
                     .flex()
@@ -57,6 +98,22 @@ impl RenderOnce for VideoItem {
                             .font_weight(FontWeight::SEMIBOLD)
                             .child(status_text),
                     )
+                    .when(!facts.is_empty(), |this| {
+                        this.child(
+                            div()
+                                .text_color(rgb(NORD4))
+                                .text_size(px(11.0))
+                                .child(facts.join(" · ")),
+                        )
+                    })
+                    .when_some(rejection, |this, reason| {
+                        this.child(
+                            div()
+                                .text_color(rgb(NORD4))
+                                .text_size(px(11.0))
+                                .child(format!("Ignorée par les règles : {reason}")),
+                        )
+                    })
                     .when(self.progress.is_some(), |this| {
                         this.child(
                             div()
@@ -64,6 +121,14 @@ impl RenderOnce for VideoItem {
                                 .mt_1()
                                 .child(ProgressBar::new(self.progress.unwrap())),
                         )
+                    })
+                    .when_some(self.details, |this, details| {
+                        this.child(
+                            div()
+                                .text_color(rgb(NORD4))
+                                .text_size(px(11.0))
+                                .child(details),
+                        )
                     }),
             )
     }
@@ -0,0 +1,262 @@
+use super::*;
+use crate::paths;
+use crate::subscriptions::{self, ExportFormat, ImportFormat, ImportPreview};
+
+/// Aperçu d'un import d'abonnements en attente de confirmation
+pub(super) struct ImportDialog {
+    format: ImportFormat,
+    preview: ImportPreview,
+}
+
+/// Nombre maximal de lignes affichées par section de l'aperçu
+const PREVIEW_LINES: usize = 50;
+
+impl NDownloaderApp {
+    pub(super) fn import_subscriptions(&mut self, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: None,
+        });
+        let existing = self.channels.clone();
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(paths))) = paths.await else {
+                return;
+            };
+            let Some(path) = paths.into_iter().next() else {
+                return;
+            };
+
+            let result = cx
+                .background_executor()
+                .spawn(async move {
+                    let content = std::fs::read_to_string(&path)?;
+                    let format = ImportFormat::detect(&path, &content);
+                    subscriptions::preview_import(&content, format, &existing)
+                        .map(|preview| ImportDialog { format, preview })
+                })
+                .await;
+            match result {
+                Ok(dialog) => {
+                    this.update(cx, |this, cx| {
+                        this.import_dialog = Some(dialog);
+                        cx.notify();
+                    })
+                    .ok();
+                }
+                Err(error) => {
+                    tracing::error!("Failed to read subscriptions: {:#}", error);
+                    Notification::error(
+                        "Import impossible",
+                        &format!("Impossible de lire les abonnements: {error:#}"),
+                    );
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn confirm_import(&mut self, cx: &mut Context<Self>) {
+        let Some(dialog) = self.import_dialog.take() else {
+            return;
+        };
+
+        let added = subscriptions::merge_import(&mut self.channels, dialog.preview.channels);
+        if !added.is_empty() {
+            self.save_channels();
+        }
+        Notification::success(
+            "Abonnements importés",
+            &format!("{} chaîne(s) ajoutée(s)", added.len()),
+        );
+        cx.notify();
+    }
+
+    fn cancel_import(&mut self, cx: &mut Context<Self>) {
+        self.import_dialog = None;
+        cx.notify();
+    }
+
+    pub(super) fn export_subscriptions(&mut self, format: ExportFormat, cx: &mut Context<Self>) {
+        let suggested_name = match format {
+            ExportFormat::Json => "chaines.json",
+            ExportFormat::Opml => "chaines.opml",
+        };
+        let path = cx.prompt_for_new_path(&paths::home_dir(), Some(suggested_name));
+        let channels = self.channels.clone();
+
+        cx.spawn(async move |_this, cx| {
+            let Ok(Ok(Some(path))) = path.await else {
+                return;
+            };
+
+            let count = channels.len();
+            let result = cx
+                .background_executor()
+                .spawn(async move {
+                    let content = subscriptions::export_channels(&channels, format)?;
+                    std::fs::write(&path, content)?;
+                    anyhow::Ok(path)
+                })
+                .await;
+            match result {
+                Ok(path) => Notification::success(
+                    "Chaînes exportées",
+                    &format!("{count} chaîne(s) exportée(s) vers {}", path.display()),
+                ),
+                Err(error) => {
+                    tracing::error!("Failed to export channels: {:#}", error);
+                    Notification::error("Export impossible", &format!("{error:#}"));
+                }
+            }
+        })
+        .detach();
+    }
+
+    pub(super) fn render_import_dialog(
+        &mut self,
+        main_content: AnyElement,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let Some(dialog) = &self.import_dialog else {
+            return main_content;
+        };
+        let preview = &dialog.preview;
+
+        let section = |title: String, color: u32, lines: Vec<String>| {
+            let hidden = lines.len().saturating_sub(PREVIEW_LINES);
+            div()
+                .flex()
+                .flex_col()
+                .gap_1()
+                .child(
+                    div()
+                        .text_color(rgb(color))
+                        .text_size(px(14.0))
+                        .font_weight(FontWeight::SEMIBOLD)
+                        .child(title),
+                )
+                .children(
+                    lines
+                        .into_iter()
+                        .take(PREVIEW_LINES)
+                        .map(|line| div().text_color(rgb(NORD4)).text_size(px(12.0)).child(line)),
+                )
+                .when(hidden > 0, |this| {
+                    this.child(
+                        div()
+                            .text_color(rgb(NORD3))
+                            .text_size(px(12.0))
+                            .child(format!("... et {hidden} autre(s)")),
+                    )
+                })
+        };
+        let describe = |channel: &Channel| format!("{} — {}", channel.display_name(), channel.url);
+        let button = |label: String, color: u32| {
+            div()
+                .px_4()
+                .py_2()
+                .bg(rgb(color))
+                .rounded_md()
+                .cursor_pointer()
+                .hover(|style| style.bg(rgb(NORD3)))
+                .child(
+                    div()
+                        .text_color(rgb(NORD6))
+                        .text_size(px(14.0))
+                        .font_weight(FontWeight::SEMIBOLD)
+                        .child(label),
+                )
+        };
+
+        let dialog_box = div()
+            .id("import-dialog")
+            .w(px(640.0))
+            .max_h(relative(0.9))
+            .overflow_y_scroll()
+            .bg(rgb(NORD1))
+            .rounded_lg()
+            .p_6()
+            .flex()
+            .flex_col()
+            .gap_4()
+            .on_mouse_down(MouseButton::Left, |_event, _phase, cx| {
+                cx.stop_propagation();
+            })
+            .child(
+                div()
+                    .text_color(rgb(NORD6))
+                    .text_size(px(18.0))
+                    .font_weight(FontWeight::BOLD)
+                    .child(format!(
+                        "Importer des abonnements ({})",
+                        dialog.format.label()
+                    )),
+            )
+            .child(section(
+                format!("{} nouvelle(s) chaîne(s)", preview.channels.len()),
+                NORD14,
+                preview.channels.iter().map(describe).collect(),
+            ))
+            .when(!preview.duplicates.is_empty(), |this| {
+                this.child(section(
+                    format!("{} déjà suivie(s), ignorée(s)", preview.duplicates.len()),
+                    NORD13,
+                    preview.duplicates.iter().map(describe).collect(),
+                ))
+            })
+            .when(!preview.invalid.is_empty(), |this| {
+                this.child(section(
+                    format!("{} URL non prise(s) en charge", preview.invalid.len()),
+                    NORD11,
+                    preview.invalid.clone(),
+                ))
+            })
+            .child(
+                div()
+                    .flex()
+                    .justify_end()
+                    .gap_2()
+                    .child(button("Annuler".to_string(), NORD3).on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| this.cancel_import(cx)),
+                    ))
+                    .when(!preview.channels.is_empty(), |this| {
+                        this.child(
+                            button(
+                                format!("Importer {} chaîne(s)", preview.channels.len()),
+                                NORD10,
+                            )
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event, _window, cx| this.confirm_import(cx)),
+                            ),
+                        )
+                    }),
+            );
+
+        div()
+            .size_full()
+            .relative()
+            .child(main_content)
+            .child(
+                div()
+                    .absolute()
+                    .top_0()
+                    .left_0()
+                    .size_full()
+                    .bg(black().opacity(0.7))
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| this.cancel_import(cx)),
+                    )
+                    .child(dialog_box),
+            )
+            .into_any_element()
+    }
+}
@@ -1,17 +1,33 @@
-use crate::downloader_queue::DownloadQueue;
+use crate::archive::DownloadArchive;
+use crate::channels::{all_tags, group_channels, Channel, ChannelStore, MonitorMode, ScanCursor};
+use crate::config::Config;
+use crate::control::{ControlNotice, ControlServer};
+use crate::download_progress;
+use crate::downloader_queue::{DownloadEvent, DownloadEventKind, DownloadQueue, DownloadStatus};
+use crate::monitor::{output_file_stem, ChannelMonitor};
 use crate::notifications::Notification;
-use crate::scanner::VideoScanner;
+use crate::rules::CompiledRules;
+use crate::scanner::{ScanMode, VideoMetadata, VideoScanner, PAGE_SIZE};
+use crate::subscriptions::ExportFormat;
+use futures::StreamExt;
 use gpui::prelude::FluentBuilder;
 use gpui::*;
-use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 pub mod actions;
+mod channel_editor;
+mod channel_groups;
 mod components;
+mod import_dialog;
+mod settings;
 mod text_input;
 
 pub use actions::*;
-use components::{ChannelItem, VideoItem};
+use channel_editor::ChannelEditor;
+use components::{platform_color, ChannelItem, VideoItem};
+use import_dialog::ImportDialog;
+use settings::SettingsForm;
 use text_input::TextInputView;
 
 // Palette Nord
@@ -25,18 +41,45 @@ const NORD8: u32 = 0x88c0d0; // Frost - bright cyan
 const NORD9: u32 = 0x81a1c1; // Frost - blue
 const NORD10: u32 = 0x5e81ac; // Frost - dark blue
 const NORD11: u32 = 0xbf616a; // Aurora - red
+const NORD12: u32 = 0xd08770; // Aurora - orange
 const NORD13: u32 = 0xebcb8b; // Aurora - yellow
 const NORD14: u32 = 0xa3be8c; // Aurora - green
 const NORD15: u32 = 0xb48ead; // Aurora - purple
 
+/// Vidéos dont les détails sont chargés en parallèle après un scan
+const ENRICH_CONCURRENCY: usize = 4;
+
 pub struct NDownloaderApp {
+    config: Config,
+    settings: Option<SettingsForm>,
+    channel_editor: Option<ChannelEditor>,
+    import_dialog: Option<ImportDialog>,
     url_input: Entity<TextInputView>,
+    /// Raison du refus du dernier lien collé
+    url_error: Option<String>,
+    channel_store: ChannelStore,
     channels: Vec<Channel>,
     selected_channel: Option<usize>,
+    /// N'affiche que les chaînes portant ce tag
+    tag_filter: Option<String>,
+    /// Groupes repliés dans la liste (`None` pour les chaînes sans groupe)
+    collapsed_groups: HashSet<Option<String>>,
+    /// Groupes dont les chaînes sont en cours de scan
+    scanning_groups: HashSet<Option<String>>,
+    /// Nouvelles vidéos trouvées au dernier scan, par URL de chaîne
+    new_videos: HashMap<String, usize>,
     videos: Vec<VideoInfo>,
     scanner: Arc<VideoScanner>,
     download_queue: Arc<DownloadQueue>,
+    archive: Arc<DownloadArchive>,
     loading: bool,
+    /// Page suivante de vidéos en cours de chargement
+    loading_more: bool,
+    /// Toutes les vidéos de la chaîne sont chargées
+    videos_exhausted: bool,
+    video_scroll: ScrollHandle,
+    /// N'affiche que les vidéos retenues par les règles de la chaîne
+    rules_preview: bool,
     download_input: Option<Entity<TextInputView>>,
     download_video: Option<DownloadingVideo>,
     downloading_videos: std::collections::HashSet<String>, // URLs des vidéos en cours de téléchargement
@@ -44,222 +87,482 @@ pub struct NDownloaderApp {
 
 #[derive(Clone)]
 struct DownloadingVideo {
+    video_id: String,
+    platform: String,
     url: String,
-    channel_name: String,
+    filesize_approx: Option<u64>,
+    channel: Channel,
     progress: f32, // 0.0 to 1.0
     speed: Option<String>,
     eta: Option<String>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-struct Channel {
-    name: String,
-    platform: Platform,
-    url: String,
-}
-
 #[derive(Clone, Debug)]
 struct VideoInfo {
+    id: String,
+    platform: String,
     title: String,
     url: String,
+    filesize_approx: Option<u64>,
+    duration: Option<f64>,
+    thumbnail: Option<String>,
+    chapters: usize,
+    /// Détails (taille, miniature, chapitres) chargés
+    enriched: bool,
     status: VideoStatus,
+    /// Raison pour laquelle les règles de la chaîne écartent la vidéo
+    rejection: Option<String>,
+    /// Nom de fichier proposé, selon le modèle de la chaîne
+    file_stem: String,
 }
 
 #[derive(Clone, Debug, PartialEq)]
 enum VideoStatus {
     NotDownloaded,
     Downloading,
+    Merging,
+    Paused,
     Downloaded,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-enum Platform {
-    YouTube,
-    Twitch,
-}
-
-impl Platform {
-    fn from_url(url: &str) -> Option<Self> {
-        if url.contains("youtube.com") || url.contains("youtu.be") {
-            Some(Platform::YouTube)
-        } else if url.contains("twitch.tv") {
-            Some(Platform::Twitch)
-        } else {
-            None
-        }
-    }
-
-    fn extract_channel_name(url: &str) -> Option<String> {
-        // Pour YouTube: youtube.com/@channel ou youtube.com/c/channel
-        if url.contains("youtube.com") {
-            if let Some(idx) = url.find("/@") {
-                let rest = &url[idx + 2..];
-                return Some(rest.split('/').next()?.to_string());
-            } else if let Some(idx) = url.find("/c/") {
-                let rest = &url[idx + 3..];
-                return Some(rest.split('/').next()?.to_string());
-            } else if let Some(idx) = url.find("/channel/") {
-                let rest = &url[idx + 9..];
-                return Some(rest.split('/').next()?.to_string());
-            }
-        }
-
-        // Pour Twitch: twitch.tv/channel
-        if url.contains("twitch.tv/") {
-            if let Some(idx) = url.find("twitch.tv/") {
-                let rest = &url[idx + 10..];
-                let channel = rest.split('/').next()?;
-                if !channel.is_empty() && channel != "videos" {
-                    return Some(channel.to_string());
-                }
-            }
-        }
-
-        None
-    }
-}
-
-const CHANNELS_CACHE_FILE: &str = "/tmp/ndownloader_channels.json";
-
-fn load_channels() -> Vec<Channel> {
-    match std::fs::read_to_string(CHANNELS_CACHE_FILE) {
-        Ok(content) => match serde_json::from_str(&content) {
-            Ok(channels) => channels,
-            Err(error) => {
-                tracing::warn!("Failed to parse channels cache: {}", error);
-                Vec::new()
-            }
-        },
-        Err(error) => {
-            tracing::debug!("No channels cache file found: {}", error);
-            Vec::new()
-        }
-    }
-}
-
-fn save_channels(channels: &[Channel]) {
-    match serde_json::to_string_pretty(channels) {
-        Ok(content) => {
-            if let Err(error) = std::fs::write(CHANNELS_CACHE_FILE, content) {
-                tracing::error!("Failed to save channels cache: {}", error);
-            }
-        }
-        Err(error) => {
-            tracing::error!("Failed to serialize channels: {}", error);
-        }
-    }
-}
-
 impl NDownloaderApp {
-    pub fn new(_window: &mut Window, cx: &mut Context<Self>) -> Self {
+    pub fn new(config: Config, _window: &mut Window, cx: &mut Context<Self>) -> Self {
         let url_input = cx.new(|cx| {
             TextInputView::new(cx)
-                .placeholder("Collez un lien YouTube ou Twitch...")
+                .placeholder("Collez un lien de chaîne (YouTube, Twitch, Kick, Vimeo...)")
                 .on_enter(move |_text| {
                     // L'action sera gérée directement par handle_add_channel
                 })
         });
 
+        let archive = Arc::new(DownloadArchive::open(config.data_dir.join("archive.json")));
+        let download_queue = Arc::new(DownloadQueue::with_config(
+            cx,
+            config.queue_config(),
+            archive.clone(),
+        ));
+        let scanner = Arc::new(VideoScanner::new(&config, archive.clone()));
+        let channel_store = ChannelStore::from_config(&config);
+
+        // Surveillance automatique des chaînes
+        let monitor_notices = ChannelMonitor::start(
+            cx,
+            channel_store.clone(),
+            scanner.clone(),
+            download_queue.clone(),
+        );
+
+        // Chaînes modifiées par la surveillance ou depuis l'API de contrôle
+        let control_notices = ControlServer::start(
+            cx,
+            channel_store.clone(),
+            scanner.clone(),
+            download_queue.clone(),
+        );
+        let mut notices = futures::stream::select(monitor_notices, control_notices);
+        cx.spawn(async move |this, cx| {
+            while let Some(notice) = notices.next().await {
+                if this
+                    .update(cx, |this, cx| this.handle_control_notice(notice, cx))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        })
+        .detach();
+
+        // Suivre l'état réel des téléchargements
+        let mut events = download_queue.subscribe();
+        cx.spawn(async move |this, cx| {
+            while let Some(event) = events.next().await {
+                if this
+                    .update(cx, |this, cx| this.handle_download_event(event, cx))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        })
+        .detach();
+
         Self {
+            config,
+            settings: None,
+            channel_editor: None,
+            import_dialog: None,
             url_input,
-            channels: load_channels(),
+            url_error: None,
+            channels: channel_store.load(),
+            channel_store,
             selected_channel: None,
+            tag_filter: None,
+            collapsed_groups: HashSet::new(),
+            scanning_groups: HashSet::new(),
+            new_videos: HashMap::new(),
             videos: Vec::new(),
-            scanner: Arc::new(VideoScanner::new()),
-            download_queue: Arc::new(DownloadQueue::new(cx)),
+            scanner,
+            download_queue,
+            archive,
             loading: false,
+            loading_more: false,
+            videos_exhausted: false,
+            video_scroll: ScrollHandle::new(),
+            rules_preview: false,
             download_input: None,
             download_video: None,
             downloading_videos: std::collections::HashSet::new(),
         }
     }
 
-    fn add_channel_from_url(&mut self, url: String) {
-        if let Some(platform) = Platform::from_url(&url) {
-            if let Some(name) = Platform::extract_channel_name(&url) {
-                // Éviter les doublons
-                if !self.channels.iter().any(|c| c.url == url) {
-                    self.channels.push(Channel {
-                        name,
-                        platform,
-                        url,
-                    });
-                    save_channels(&self.channels);
-                }
-            }
+    /// Note la date de scan dans la liste affichée et sur disque, sans réécrire
+    /// les curseurs que la surveillance a pu avancer entre-temps
+    fn record_scan_date(&mut self, url: &str) {
+        if let Some(tracked) = self.channels.iter_mut().find(|c| c.url == url) {
+            tracked.last_scan = Some(chrono::Utc::now());
+        }
+        if let Err(error) = self.channel_store.record_scan_date(url) {
+            tracing::warn!("Failed to record scan date: {:#}", error);
+        }
+    }
+
+    fn save_channels(&self) {
+        if let Err(error) = self.channel_store.save(&self.channels) {
+            tracing::error!("Failed to save channels: {:#}", error);
+            Notification::error("Chaînes non enregistrées", &error.to_string());
+        }
+    }
+
+    /// Ajoute la chaîne du lien, ou renvoie la raison du refus
+    fn add_channel_from_url(&mut self, url: String, cx: &mut Context<Self>) -> Result<(), String> {
+        let channel = Channel::parse_or_generic(&url).map_err(|error| error.to_string())?;
+        // Éviter les doublons
+        if self.channels.iter().any(|c| c.is_same(&channel)) {
+            return Err(format!("La chaîne {} est déjà suivie", channel.display_name()));
         }
+        self.channels.push(channel.clone());
+        self.save_channels();
+
+        if channel.channel_id.is_none() {
+            cx.spawn(async move |this, cx| {
+                let mut resolved = channel;
+                VideoScanner::fill_channel_id(&mut resolved).await;
+                let Some(channel_id) = resolved.channel_id else {
+                    return;
+                };
+                this.update(cx, |this, cx| {
+                    if let Some(tracked) = this.channels.iter_mut().find(|c| c.url == resolved.url) {
+                        tracked.channel_id = Some(channel_id);
+                        this.save_channels();
+                    }
+                    cx.notify();
+                })
+                .ok();
+            })
+            .detach();
+        }
+        Ok(())
     }
 
     fn handle_add_channel(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
         let url = self.url_input.read(cx).value();
         if !url.trim().is_empty() {
-            self.add_channel_from_url(url);
-            // Clear the input
-            self.url_input.update(cx, |input, _cx| {
-                input.clear();
-            });
+            match self.add_channel_from_url(url, cx) {
+                Ok(()) => {
+                    self.url_error = None;
+                    // Clear the input
+                    self.url_input.update(cx, |input, _cx| {
+                        input.clear();
+                    });
+                }
+                Err(error) => {
+                    tracing::warn!("Rejected channel link: {}", error);
+                    self.url_error = Some(error);
+                }
+            }
             cx.notify();
         }
     }
 
-    fn select_channel(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+    fn select_channel(&mut self, index: usize, _window: &mut Window, cx: &mut Context<Self>) {
         self.selected_channel = Some(index);
-        self.loading = true;
-        self.videos.clear();
+        self.rules_preview = false;
+        self.load_videos(index, ScanMode::first_page(), cx);
+    }
+
+    /// Charge la page suivante quand la liste arrive en bas
+    fn load_more_videos(&mut self, cx: &mut Context<Self>) {
+        let Some(index) = self.selected_channel else {
+            return;
+        };
+        if self.loading || self.loading_more || self.videos_exhausted {
+            return;
+        }
+        let start = self.videos.len();
+        self.load_videos(index, ScanMode::Page { start, count: PAGE_SIZE }, cx);
+    }
+
+    /// Scanne la chaîne sélectionnée. Une page suivante est ajoutée à la liste,
+    /// les autres scans la remplacent.
+    fn load_videos(&mut self, index: usize, mode: ScanMode, cx: &mut Context<Self>) {
+        let Some(channel) = self.channels.get(index).cloned() else {
+            return;
+        };
+        let append = matches!(mode, ScanMode::Page { start, .. } if start > 0);
+        if append {
+            self.loading_more = true;
+        } else {
+            self.loading = true;
+            self.loading_more = false;
+            self.videos_exhausted = false;
+            self.videos.clear();
+        }
         cx.notify();
 
-        let channel_url = self.channels[index].url.clone();
-        let channel_name = self.channels[index].name.clone();
         let scanner = self.scanner.clone();
+        let rules = match channel.rules.compile() {
+            Ok(rules) => Some(rules),
+            Err(error) => {
+                tracing::warn!("Invalid rules for {}: {}", channel.name, error);
+                Notification::error("Règles invalides", &error.to_string());
+                None
+            }
+        };
 
-        cx.spawn_in(window, async move |this, cx| {
-            let videos_result = scanner.scan_channel_videos(&channel_url).await;
-
-            this.update(cx, |this, cx| {
-                match videos_result {
-                    Ok(metadata_videos) => {
-                        this.videos = metadata_videos
-                            .into_iter()
-                            .map(|meta| {
-                                let is_downloaded = scanner
-                                    .is_video_downloaded(&channel_name, meta.duration)
-                                    .is_some();
-                                let is_downloading = this.downloading_videos.contains(&meta.url);
-
-                                let status = if is_downloaded {
-                                    VideoStatus::Downloaded
-                                } else if is_downloading {
-                                    VideoStatus::Downloading
-                                } else {
-                                    VideoStatus::NotDownloaded
-                                };
+        cx.spawn(async move |this, cx| {
+            let videos_result = scanner.fetch_channel_videos(&channel, mode).await;
+
+            // Liste à plat affichée tout de suite
+            let pending = this.update(cx, |this, cx| {
+                let pending = if this.is_selected(&channel) {
+                    this.show_videos(&channel, rules.as_ref(), videos_result, mode)
+                } else {
+                    // Une autre chaîne a été ouverte pendant le scan
+                    Vec::new()
+                };
+                cx.notify();
+                pending
+            });
+            let Ok(pending) = pending else {
+                return;
+            };
 
-                                VideoInfo {
-                                    title: meta.title,
-                                    url: meta.url,
-                                    status,
-                                }
-                            })
-                            .collect();
-                    }
+            // Puis les détails de chaque vidéo, ligne par ligne
+            let mut details = futures::stream::iter(pending)
+                .map(|video| {
+                    let scanner = scanner.clone();
+                    async move { scanner.enrich_video(&video).await }
+                })
+                .buffer_unordered(ENRICH_CONCURRENCY);
+            while let Some(result) = details.next().await {
+                let video = match result {
+                    Ok(video) => video,
                     Err(error) => {
-                        tracing::error!("Failed to scan channel videos: {}", error);
+                        tracing::debug!("Failed to load video details: {:#}", error);
+                        continue;
+                    }
+                };
+                let updated = this.update(cx, |this, cx| {
+                    if !this.is_selected(&channel) {
+                        return false;
                     }
+                    let info = this.video_info(&channel, rules.as_ref(), video);
+                    if let Some(row) = this.videos.iter_mut().find(|row| row.url == info.url) {
+                        *row = info;
+                    }
+                    cx.notify();
+                    true
+                });
+                if !matches!(updated, Ok(true)) {
+                    return;
                 }
+            }
+        })
+        .detach();
+    }
 
-                this.loading = false;
-                cx.notify();
+    fn is_selected(&self, channel: &Channel) -> bool {
+        self.selected_channel
+            .and_then(|index| self.channels.get(index))
+            .is_some_and(|selected| selected.url == channel.url)
+    }
+
+    /// Affiche le résultat d'un scan et renvoie les vidéos dont les détails
+    /// restent à charger
+    fn show_videos(
+        &mut self,
+        channel: &Channel,
+        rules: Option<&CompiledRules>,
+        result: anyhow::Result<(Vec<VideoMetadata>, ScanCursor)>,
+        mode: ScanMode,
+    ) -> Vec<VideoMetadata> {
+        self.loading = false;
+        self.loading_more = false;
+        // L'affichage ne fait jamais avancer le curseur de la surveillance
+        let metadata_videos = match result {
+            Ok((videos, _)) => videos,
+            Err(error) => {
+                tracing::error!("Failed to scan channel videos: {}", error);
+                Notification::error("Scan impossible", &format!("{error:#}"));
+                return Vec::new();
+            }
+        };
+
+        self.videos_exhausted = match mode {
+            ScanMode::Page { count, .. } => metadata_videos.len() < count,
+            ScanMode::Incremental | ScanMode::Backfill => true,
+        };
+        let append = matches!(mode, ScanMode::Page { start, .. } if start > 0);
+        let metadata_videos: Vec<VideoMetadata> = metadata_videos
+            .into_iter()
+            .filter(|meta| !append || !self.videos.iter().any(|v| v.url == meta.url))
+            .collect();
+        let pending: Vec<VideoMetadata> = metadata_videos
+            .iter()
+            .filter(|meta| !meta.enriched)
+            .cloned()
+            .collect();
+        let videos: Vec<VideoInfo> = metadata_videos
+            .into_iter()
+            .map(|meta| self.video_info(channel, rules, meta))
+            .collect();
+
+        if append {
+            self.videos.extend(videos);
+            return pending;
+        }
+
+        self.videos = videos;
+        let new_count = self
+            .videos
+            .iter()
+            .filter(|video| {
+                video.status == VideoStatus::NotDownloaded && video.rejection.is_none()
             })
+            .count();
+        self.new_videos.insert(channel.url.clone(), new_count);
+        self.record_scan_date(&channel.url);
+        pending
+    }
+
+    /// Ligne de la liste pour une vidéo, avec son état local
+    fn video_info(
+        &self,
+        channel: &Channel,
+        rules: Option<&CompiledRules>,
+        meta: VideoMetadata,
+    ) -> VideoInfo {
+        // Tâches de la queue (y compris celles rechargées au démarrage)
+        let tasks = self.download_queue.get_tasks();
+        let today = chrono::Utc::now().date_naive();
+        let is_downloaded = self
+            .scanner
+            .is_video_downloaded(&channel.name, &meta)
+            .is_some();
+        let rejection = rules
+            .and_then(|rules| rules.evaluate(&meta, today).err())
+            .map(|rejection| rejection.to_string());
+        let task_status = tasks
+            .iter()
+            .rev()
+            .find(|t| t.video_url == meta.url)
+            .map(|t| t.status.clone());
+        let is_downloading = self.downloading_videos.contains(&meta.url)
+            || matches!(
+                task_status,
+                Some(DownloadStatus::Queued | DownloadStatus::Downloading)
+            );
+
+        let status = if is_downloaded {
+            VideoStatus::Downloaded
+        } else if task_status == Some(DownloadStatus::Paused) {
+            VideoStatus::Paused
+        } else if is_downloading {
+            VideoStatus::Downloading
+        } else {
+            VideoStatus::NotDownloaded
+        };
+
+        VideoInfo {
+            file_stem: output_file_stem(&meta, channel.filename_template.as_deref()),
+            platform: meta.platform(),
+            id: meta.id,
+            title: meta.title,
+            url: meta.url,
+            filesize_approx: meta.filesize_approx,
+            duration: meta.duration,
+            thumbnail: meta.thumbnail,
+            chapters: meta.chapters.len(),
+            enriched: meta.enriched,
+            status,
+            rejection,
+        }
+    }
+
+    fn import_ytdlp_archive(&mut self, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: None,
+        });
+        let archive = self.archive.clone();
+
+        cx.spawn(async move |_this, cx| {
+            let Ok(Ok(Some(paths))) = paths.await else {
+                return;
+            };
+            let Some(path) = paths.into_iter().next() else {
+                return;
+            };
+
+            let result = cx
+                .background_executor()
+                .spawn(async move { archive.import_ytdlp_archive(&path) })
+                .await;
+            match result {
+                Ok(count) => Notification::success(
+                    "Archive importée",
+                    &format!("{count} vidéo(s) ajoutée(s) à l'archive"),
+                ),
+                Err(error) => {
+                    tracing::error!("Failed to import yt-dlp archive: {}", error);
+                    Notification::error(
+                        "Import impossible",
+                        &format!("Impossible de lire l'archive: {error}"),
+                    );
+                }
+            }
         })
         .detach();
     }
 
+    /// Désactivée → notification → téléchargement automatique → désactivée
+    fn cycle_channel_monitoring(&mut self, index: usize, cx: &mut Context<Self>) {
+        let Some(channel) = self.channels.get_mut(index) else {
+            return;
+        };
+
+        let monitor = &mut channel.monitor;
+        match (monitor.enabled, monitor.mode) {
+            (false, _) => {
+                monitor.enabled = true;
+                monitor.mode = MonitorMode::NotifyOnly;
+            }
+            (true, MonitorMode::NotifyOnly) => monitor.mode = MonitorMode::AutoDownload,
+            (true, MonitorMode::AutoDownload) => monitor.enabled = false,
+        }
+
+        self.save_channels();
+        cx.notify();
+    }
+
     fn delete_channel(&mut self, index: usize, cx: &mut Context<Self>) {
         if index < self.channels.len() {
             self.channels.remove(index);
 
             // Sauvegarder les changements
-            save_channels(&self.channels);
+            self.save_channels();
 
             // Si on était sur cette chaîne, revenir à la liste
             if self.selected_channel == Some(index) {
@@ -276,8 +579,16 @@ impl NDownloaderApp {
         }
     }
 
-    fn go_back(&mut self, _: &GoBack, _window: &mut Window, _cx: &mut Context<Self>) {
+    fn go_back(&mut self, _: &GoBack, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.settings.take().is_some()
+            || self.channel_editor.take().is_some()
+            || self.import_dialog.take().is_some()
+        {
+            cx.notify();
+            return;
+        }
         self.selected_channel = None;
+        self.rules_preview = false;
         self.videos.clear();
     }
 
@@ -294,13 +605,19 @@ impl NDownloaderApp {
         self.cancel_download(cx);
     }
 
-    fn start_download(&mut self, video_url: String, channel_name: String, cx: &mut Context<Self>) {
-        let input =
-            cx.new(|cx| TextInputView::new(cx).placeholder("Nom du fichier (sans extension)..."));
+    fn start_download(&mut self, video: &VideoInfo, channel: Channel, cx: &mut Context<Self>) {
+        let input = cx.new(|cx| {
+            TextInputView::new(cx)
+                .placeholder("Nom du fichier (sans extension)...")
+                .with_value(video.file_stem.clone())
+        });
         self.download_input = Some(input);
         self.download_video = Some(DownloadingVideo {
-            url: video_url,
-            channel_name,
+            video_id: video.id.clone(),
+            platform: video.platform.clone(),
+            url: video.url.clone(),
+            filesize_approx: video.filesize_approx,
+            channel,
             progress: 0.0,
             speed: None,
             eta: None,
@@ -314,6 +631,131 @@ impl NDownloaderApp {
         cx.notify();
     }
 
+    /// Identifiant de la tâche de téléchargement associée à une vidéo
+    fn task_id_for_url(&self, video_url: &str) -> Option<String> {
+        self.download_queue
+            .get_tasks()
+            .into_iter()
+            .rev()
+            .find(|t| t.video_url == video_url)
+            .map(|t| t.video_id)
+    }
+
+    fn set_video_status(&mut self, video_url: &str, status: VideoStatus) {
+        if let Some(video) = self.videos.iter_mut().find(|v| v.url == video_url) {
+            video.status = status;
+        }
+    }
+
+    fn pause_video_download(&mut self, video_url: &str) {
+        if let Some(task_id) = self.task_id_for_url(video_url) {
+            self.download_queue.pause(&task_id);
+        }
+    }
+
+    fn resume_video_download(&mut self, video_url: &str) {
+        if let Some(task_id) = self.task_id_for_url(video_url) {
+            self.download_queue.resume(&task_id);
+        }
+    }
+
+    fn cancel_video_download(&mut self, video_url: &str) {
+        if let Some(task_id) = self.task_id_for_url(video_url) {
+            self.download_queue.cancel(&task_id);
+        }
+    }
+
+    fn handle_control_notice(&mut self, notice: ControlNotice, cx: &mut Context<Self>) {
+        match notice {
+            ControlNotice::ChannelsChanged => {
+                let selected_url = self
+                    .selected_channel
+                    .and_then(|index| self.channels.get(index))
+                    .map(|channel| channel.url.clone());
+                self.channels = self.channel_store.load();
+                self.selected_channel = selected_url
+                    .and_then(|url| self.channels.iter().position(|c| c.url == url));
+                if self.selected_channel.is_none() {
+                    self.videos.clear();
+                }
+            }
+        }
+        cx.notify();
+    }
+
+    /// Met à jour l'interface à partir d'un événement de la queue
+    fn handle_download_event(&mut self, event: DownloadEvent, cx: &mut Context<Self>) {
+        let video_url = event.video_url;
+        match event.kind {
+            DownloadEventKind::Queued => {
+                self.downloading_videos.insert(video_url.clone());
+                self.set_video_status(&video_url, VideoStatus::Downloading);
+            }
+            DownloadEventKind::Started { attempt, .. } => {
+                self.downloading_videos.insert(video_url.clone());
+                self.set_video_status(&video_url, VideoStatus::Downloading);
+                if attempt == 1 {
+                    Notification::info(
+                        "Téléchargement démarré",
+                        &format!("Téléchargement de {} en cours...", event.title),
+                    );
+                }
+            }
+            DownloadEventKind::Progress { progress, detail } => {
+                if let Some(ref mut video) = self.download_video {
+                    if video.url == video_url {
+                        video.progress = progress;
+                        video.speed = detail.speed.map(download_progress::format_speed);
+                        video.eta = detail.eta.map(download_progress::format_eta);
+                    }
+                }
+            }
+            DownloadEventKind::Merging => {
+                self.set_video_status(&video_url, VideoStatus::Merging);
+            }
+            DownloadEventKind::Completed { .. } => {
+                self.downloading_videos.remove(&video_url);
+                self.set_video_status(&video_url, VideoStatus::Downloaded);
+                Notification::success(
+                    "Téléchargement terminé",
+                    &format!("{} a été téléchargé avec succès", event.title),
+                );
+            }
+            DownloadEventKind::Failed { reason } => {
+                self.downloading_videos.remove(&video_url);
+                self.set_video_status(&video_url, VideoStatus::NotDownloaded);
+                Notification::error(
+                    "Erreur de téléchargement",
+                    &format!("{}: {reason}", event.title),
+                );
+            }
+            DownloadEventKind::Retrying { reason, delay_secs } => {
+                tracing::warn!(
+                    "{}: {} (nouvelle tentative dans {}s)",
+                    event.title,
+                    reason,
+                    delay_secs
+                );
+            }
+            DownloadEventKind::Paused => {
+                self.set_video_status(&video_url, VideoStatus::Paused);
+            }
+            DownloadEventKind::Interrupted => {
+                tracing::info!("{} reprendra au prochain lancement", event.title);
+            }
+            DownloadEventKind::Cancelled => {
+                self.downloading_videos.remove(&video_url);
+                self.set_video_status(&video_url, VideoStatus::NotDownloaded);
+                Notification::info(
+                    "Téléchargement annulé",
+                    "Les fichiers partiels ont été supprimés",
+                );
+            }
+        }
+
+        cx.notify();
+    }
+
     fn confirm_download(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
         let Some(input) = &self.download_input else {
             return;
@@ -328,21 +770,37 @@ impl NDownloaderApp {
             return;
         }
 
-        let channel_name = video.channel_name.clone();
+        let channel = video.channel.clone();
         let video_url = video.url.clone();
+        let video_id = video.video_id.clone();
+        let platform = video.platform.clone();
         let download_queue = self.download_queue.clone();
         let scanner = self.scanner.clone();
 
-        // Trouver le meilleur disque de stockage
-        let storage_path = match scanner.find_best_storage_path() {
-            Ok(path) => path,
+        // Trouver le meilleur disque de stockage (refusé si la vidéo n'y tient pas)
+        let storage = match scanner.find_best_storage_path(
+            &channel.name,
+            channel.storage_root.as_deref(),
+            video.filesize_approx,
+        ) {
+            Ok(storage) => storage,
             Err(error) => {
                 tracing::error!("Failed to find storage path: {}", error);
+                Notification::error("Téléchargement refusé", &error.to_string());
                 return;
             }
         };
+        if storage.rerouted {
+            Notification::info(
+                "Téléchargement redirigé",
+                &format!(
+                    "Espace insuffisant sur le disque habituel, enregistrement dans {}",
+                    storage.path.display()
+                ),
+            );
+        }
 
-        let output_path = format!("{}/{}/{}.mp4", storage_path, channel_name, filename.trim());
+        let output_path = channel.output_path(&storage.path, filename.trim());
 
         // Marquer comme en cours de téléchargement
         self.downloading_videos.insert(video_url.clone());
@@ -355,23 +813,18 @@ impl NDownloaderApp {
             }
         }
 
-        // Lancer le téléchargement
-        let output_path_buf = std::path::PathBuf::from(&output_path);
-        let filename_clone = filename.trim().to_string();
-
-        // Notification de début
-        Notification::info(
-            "Téléchargement démarré",
-            &format!("Téléchargement de {filename_clone} en cours..."),
-        );
+        // Lancer le téléchargement (la suite est suivie via les événements de la queue)
+        let filename = filename.trim().to_string();
 
         cx.spawn(async move |this, cx| {
             if let Err(error) = download_queue
                 .add_download(
-                    filename.clone(),
+                    video_id,
+                    platform,
                     video_url.clone(),
-                    filename.clone(),
-                    output_path_buf.clone(),
+                    filename,
+                    output_path,
+                    channel.format,
                 )
                 .await
             {
@@ -383,72 +836,10 @@ impl NDownloaderApp {
 
                 this.update(cx, |this, cx| {
                     this.downloading_videos.remove(&video_url);
-                    for video in &mut this.videos {
-                        if video.url == video_url {
-                            video.status = VideoStatus::NotDownloaded;
-                            break;
-                        }
-                    }
+                    this.set_video_status(&video_url, VideoStatus::NotDownloaded);
                     cx.notify();
                 })
                 .ok();
-                return;
-            }
-
-            // Polling: attendre que le fichier existe vraiment
-            let mut progress = 0.0;
-            loop {
-                cx.background_executor()
-                    .timer(std::time::Duration::from_secs(2))
-                    .await;
-
-                // Simuler la progression (incrémenter jusqu'à 90%)
-                if progress < 0.9 {
-                    progress += 0.1;
-                    this.update(cx, |this, cx| {
-                        if let Some(ref mut video) = this.download_video {
-                            if video.url == video_url {
-                                video.progress = progress;
-                                cx.notify();
-                            }
-                        }
-                    })
-                    .ok();
-                }
-
-                if output_path_buf.exists() {
-                    // Fichier existe, téléchargement terminé !
-                    this.update(cx, |this, cx| {
-                        if let Some(ref mut video) = this.download_video {
-                            if video.url == video_url {
-                                video.progress = 1.0;
-                                cx.notify();
-                            }
-                        }
-                    })
-                    .ok();
-
-                    Notification::success(
-                        "Téléchargement terminé",
-                        &format!("{filename_clone} a été téléchargé avec succès"),
-                    );
-
-                    this.update(cx, |this, cx| {
-                        this.downloading_videos.remove(&video_url);
-                        for video in &mut this.videos {
-                            if video.url == video_url {
-                                video.status = VideoStatus::Downloaded;
-                                break;
-                            }
-                        }
-                        cx.notify();
-                    })
-                    .ok();
-                    break;
-                }
-
-                // Timeout après 2 heures (en cas de problème)
-                // TODO: améliorer avec une vraie vérification de l'état de la queue
             }
         })
         .detach();
@@ -462,6 +853,10 @@ impl NDownloaderApp {
 
 impl Render for NDownloaderApp {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if self.settings.is_some() {
+            return self.render_settings(cx);
+        }
+
         let main_content = if let Some(channel_index) = self.selected_channel {
             self.render_video_list(channel_index, cx).into_any_element()
         } else {
@@ -472,6 +867,12 @@ impl Render for NDownloaderApp {
         if self.download_input.is_some() {
             return self.render_download_overlay(main_content, cx);
         }
+        if self.channel_editor.is_some() {
+            return self.render_channel_editor(main_content, cx);
+        }
+        if self.import_dialog.is_some() {
+            return self.render_import_dialog(main_content, cx);
+        }
 
         main_content
     }
@@ -479,6 +880,14 @@ impl Render for NDownloaderApp {
 
 impl NDownloaderApp {
     fn render_channel_list(&mut self, cx: &mut Context<Self>) -> AnyElement {
+        let tags = all_tags(&self.channels);
+        // Un tag retiré de toutes les chaînes ne filtre plus rien
+        let tag_filter = self
+            .tag_filter
+            .as_deref()
+            .filter(|tag| tags.iter().any(|t| t == tag));
+        let groups = group_channels(&self.channels, tag_filter);
+
         // Sinon, afficher la liste des chaînes
         div()
             .on_action(cx.listener(Self::go_back))
@@ -507,7 +916,7 @@ impl NDownloaderApp {
                         div()
                             .text_color(rgb(NORD4))
                             .text_size(px(14.0))
-                            .child("Automatic video downloader for Twitch and YouTube")
+                            .child("Automatic video downloader for YouTube, Twitch, Kick, Vimeo, PeerTube and more")
                     )
             )
             .child(
@@ -521,16 +930,40 @@ impl NDownloaderApp {
                     .rounded_md()
                     .child(
                         div()
-                            .text_color(rgb(NORD6))
-                            .text_size(px(16.0))
-                            .font_weight(FontWeight::SEMIBOLD)
-                            .child("Ajouter une chaîne")
+                            .flex()
+                            .items_center()
+                            .justify_between()
+                            .child(
+                                div()
+                                    .text_color(rgb(NORD6))
+                                    .text_size(px(16.0))
+                                    .font_weight(FontWeight::SEMIBOLD)
+                                    .child("Ajouter une chaîne")
+                            )
+                            .child(
+                                div()
+                                    .px_3()
+                                    .py_1()
+                                    .bg(rgb(NORD2))
+                                    .rounded_md()
+                                    .cursor_pointer()
+                                    .hover(|style| style.bg(rgb(NORD3)))
+                                    .on_mouse_down(MouseButton::Left, cx.listener(|this, _event, _window, cx| {
+                                        this.open_settings(cx);
+                                    }))
+                                    .child(
+                                        div()
+                                            .text_color(rgb(NORD6))
+                                            .text_size(px(13.0))
+                                            .child("Réglages")
+                                    )
+                            )
                     )
                     .child(
                         div()
                             .text_color(rgb(NORD4))
                             .text_size(px(13.0))
-                            .child("Collez un lien YouTube ou Twitch (l'app détectera automatiquement la plateforme)")
+                            .child("Collez un lien YouTube, Twitch, Kick, Vimeo, PeerTube, Dailymotion ou Odysee (l'app détectera automatiquement la plateforme, les autres sites passent par yt-dlp)")
                     )
                     .child(
                         // URL input and button
@@ -577,6 +1010,59 @@ impl NDownloaderApp {
                                     )
                             )
                     )
+                    .when_some(self.url_error.clone(), |this, error| {
+                        this.child(
+                            div()
+                                .text_color(rgb(NORD11))
+                                .text_size(px(13.0))
+                                .child(error)
+                        )
+                    })
+                    .child(
+                        div()
+                            .text_color(rgb(NORD9))
+                            .text_size(px(13.0))
+                            .cursor_pointer()
+                            .hover(|style| style.text_color(rgb(NORD8)))
+                            .on_mouse_down(MouseButton::Left, cx.listener(|this, _event, _window, cx| {
+                                this.import_ytdlp_archive(cx);
+                            }))
+                            .child("Importer une archive yt-dlp (--download-archive)...")
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .gap_4()
+                            .text_color(rgb(NORD9))
+                            .text_size(px(13.0))
+                            .child(
+                                div()
+                                    .cursor_pointer()
+                                    .hover(|style| style.text_color(rgb(NORD8)))
+                                    .on_mouse_down(MouseButton::Left, cx.listener(|this, _event, _window, cx| {
+                                        this.import_subscriptions(cx);
+                                    }))
+                                    .child("Importer des abonnements (OPML, Takeout, Twitch, URL)...")
+                            )
+                            .child(
+                                div()
+                                    .cursor_pointer()
+                                    .hover(|style| style.text_color(rgb(NORD8)))
+                                    .on_mouse_down(MouseButton::Left, cx.listener(|this, _event, _window, cx| {
+                                        this.export_subscriptions(ExportFormat::Json, cx);
+                                    }))
+                                    .child("Exporter en JSON...")
+                            )
+                            .child(
+                                div()
+                                    .cursor_pointer()
+                                    .hover(|style| style.text_color(rgb(NORD8)))
+                                    .on_mouse_down(MouseButton::Left, cx.listener(|this, _event, _window, cx| {
+                                        this.export_subscriptions(ExportFormat::Opml, cx);
+                                    }))
+                                    .child("Exporter en OPML...")
+                            )
+                    )
             )
             .child(
                 // Channels list section
@@ -597,6 +1083,7 @@ impl NDownloaderApp {
                             .mb_2()
                             .child(format!("Chaînes surveillées ({})", self.channels.len()))
                     )
+                    .when(!tags.is_empty(), |this| this.child(self.render_tag_filter(tags, cx)))
                     .child(
                         if self.channels.is_empty() {
                             div()
@@ -616,58 +1103,135 @@ impl NDownloaderApp {
                                 .gap_2()
                                 .size_full()
                                 .overflow_y_scroll()
-                                .children(self.channels.iter().enumerate().map(|(index, channel)| {
-                                    div()
-                                        .flex()
-                                        .p_3()
-                                        .bg(rgb(NORD2))
-                                        .rounded_md()
-                                        .cursor_pointer()
-                                        .hover(|style| style.bg(rgb(NORD3)))
-                                        .on_mouse_down(MouseButton::Left, cx.listener(move |this, _event, window, cx| {
-                                            this.select_channel(index, window, cx);
-                                        }))
-                                        .child(
-                                            div()
-                                                .flex_1()
-                                                .child(ChannelItem::new(channel.clone()))
-                                        )
-                                        .child(
-                                            div()
-                                                .px_2()
-                                                .py_1()
-                                                .bg(rgb(NORD11))
-                                                .rounded_sm()
-                                                .cursor_pointer()
-                                                .hover(|style| style.bg(rgb(0x8f4149)))
-                                                .on_mouse_down(MouseButton::Left, cx.listener(move |this, _event, _window, cx| {
-                                                    this.delete_channel(index, cx);
-                                                    cx.stop_propagation();
-                                                }))
-                                                .child(
-                                                    div()
-                                                        .text_color(rgb(NORD6))
-                                                        .text_size(px(12.0))
-                                                        .font_weight(FontWeight::BOLD)
-                                                        .child("✕")
-                                                )
-                                        )
-                                }))
+                                .children(groups.into_iter().map(|group| self.render_channel_group(group, cx)))
                                 .into_any_element()
                         }
                     )
             )
             .into_any_element()
     }
+
+    fn render_channel_row(&self, index: usize, cx: &mut Context<Self>) -> Div {
+        let channel = &self.channels[index];
+        div()
+            .flex()
+            .p_3()
+            .bg(rgb(NORD2))
+            .rounded_md()
+            .cursor_pointer()
+            .hover(|style| style.bg(rgb(NORD3)))
+            .on_mouse_down(MouseButton::Left, cx.listener(move |this, _event, window, cx| {
+                this.select_channel(index, window, cx);
+            }))
+            .child(
+                div()
+                    .flex_1()
+                    .child(ChannelItem::new(channel.clone()))
+            )
+            .when_some(self.new_videos.get(&channel.url).filter(|count| **count > 0), |this, count| {
+                this.child(
+                    div()
+                        .px_2()
+                        .py_1()
+                        .mr_2()
+                        .bg(rgb(NORD13))
+                        .rounded_sm()
+                        .child(
+                            div()
+                                .text_color(rgb(NORD0))
+                                .text_size(px(12.0))
+                                .font_weight(FontWeight::BOLD)
+                                .child(format!("{count} nouvelle(s)"))
+                        )
+                )
+            })
+            .child(
+                // Mode de surveillance (cliquer pour changer)
+                div()
+                    .px_2()
+                    .py_1()
+                    .mr_2()
+                    .bg(rgb(match (channel.monitor.is_active(), channel.monitor.mode) {
+                        (false, _) => NORD1,
+                        (true, MonitorMode::NotifyOnly) => NORD10,
+                        (true, MonitorMode::AutoDownload) => NORD14,
+                    }))
+                    .rounded_sm()
+                    .cursor_pointer()
+                    .hover(|style| style.bg(rgb(NORD3)))
+                    .on_mouse_down(MouseButton::Left, cx.listener(move |this, _event, _window, cx| {
+                        this.cycle_channel_monitoring(index, cx);
+                        cx.stop_propagation();
+                    }))
+                    .child(
+                        div()
+                            .text_color(rgb(NORD6))
+                            .text_size(px(12.0))
+                            .child(match (channel.monitor.enabled, channel.monitor.mode) {
+                                (false, _) => "Surveillance: non",
+                                (true, _) if channel.monitor.paused => "Surveillance: suspendue",
+                                (true, MonitorMode::NotifyOnly) => "Surveillance: notification",
+                                (true, MonitorMode::AutoDownload) => "Surveillance: auto",
+                            })
+                    )
+            )
+            .child(
+                div()
+                    .px_2()
+                    .py_1()
+                    .mr_2()
+                    .bg(rgb(NORD1))
+                    .rounded_sm()
+                    .cursor_pointer()
+                    .hover(|style| style.bg(rgb(NORD3)))
+                    .on_mouse_down(MouseButton::Left, cx.listener(move |this, _event, _window, cx| {
+                        this.open_channel_editor(index, cx);
+                        cx.stop_propagation();
+                    }))
+                    .child(
+                        div()
+                            .text_color(rgb(NORD6))
+                            .text_size(px(12.0))
+                            .child("Éditer")
+                    )
+            )
+            .child(
+                div()
+                    .px_2()
+                    .py_1()
+                    .bg(rgb(NORD11))
+                    .rounded_sm()
+                    .cursor_pointer()
+                    .hover(|style| style.bg(rgb(0x8f4149)))
+                    .on_mouse_down(MouseButton::Left, cx.listener(move |this, _event, _window, cx| {
+                        this.delete_channel(index, cx);
+                        cx.stop_propagation();
+                    }))
+                    .child(
+                        div()
+                            .text_color(rgb(NORD6))
+                            .text_size(px(12.0))
+                            .font_weight(FontWeight::BOLD)
+                            .child("✕")
+                    )
+            )
+    }
 }
 
 impl NDownloaderApp {
     fn render_video_list(&mut self, channel_index: usize, cx: &mut Context<Self>) -> Div {
         let channel = &self.channels[channel_index];
-        let platform_color = match channel.platform {
-            Platform::YouTube => rgb(NORD11),
-            Platform::Twitch => rgb(NORD15),
-        };
+        let platform_color = platform_color(&channel.platform);
+        let matching_count = self
+            .videos
+            .iter()
+            .filter(|video| video.rejection.is_none())
+            .count();
+        let visible_videos: Vec<&VideoInfo> = self
+            .videos
+            .iter()
+            .filter(|video| !self.rules_preview || video.rejection.is_none())
+            .collect();
 
         div()
             .flex()
@@ -716,10 +1280,7 @@ impl NDownloaderApp {
                                         .text_color(rgb(NORD6))
                                         .text_size(px(12.0))
                                         .font_weight(FontWeight::BOLD)
-                                        .child(match channel.platform {
-                                            Platform::YouTube => "YouTube",
-                                            Platform::Twitch => "Twitch",
-                                        }),
+                                        .child(channel.platform.extractor().label()),
                                 ),
                             )
                             .child(
@@ -727,7 +1288,7 @@ impl NDownloaderApp {
                                     .text_color(rgb(NORD6))
                                     .text_size(px(20.0))
                                     .font_weight(FontWeight::BOLD)
-                                    .child(channel.name.clone()),
+                                    .child(channel.display_name().to_string()),
                             ),
                     ),
             )
@@ -744,12 +1305,88 @@ impl NDownloaderApp {
                     .overflow_hidden()
                     .child(
                         div()
-                            .text_color(rgb(NORD6))
-                            .text_size(px(16.0))
-                            .font_weight(FontWeight::SEMIBOLD)
+                            .flex()
+                            .items_center()
+                            .justify_between()
                             .mb_2()
-                            .child(format!("Vidéos disponibles ({})", self.videos.len())),
+                            .child(
+                                div()
+                                    .text_color(rgb(NORD6))
+                                    .text_size(px(16.0))
+                                    .font_weight(FontWeight::SEMIBOLD)
+                                    .child(if self.rules_preview {
+                                        format!(
+                                            "Vidéos retenues par les règles ({} sur {})",
+                                            matching_count,
+                                            self.videos.len()
+                                        )
+                                    } else {
+                                        format!("Vidéos disponibles ({})", self.videos.len())
+                                    }),
+                            )
+                            .when(!channel.rules.is_empty(), |this| {
+                                this.child(
+                                    // Simulation: vidéos que la surveillance prendrait
+                                    div()
+                                        .px_3()
+                                        .py_1()
+                                        .bg(rgb(if self.rules_preview { NORD10 } else { NORD2 }))
+                                        .rounded_md()
+                                        .cursor_pointer()
+                                        .hover(|style| style.bg(rgb(NORD3)))
+                                        .on_mouse_down(
+                                            MouseButton::Left,
+                                            cx.listener(|this, _event, _window, cx| {
+                                                this.rules_preview = !this.rules_preview;
+                                                cx.notify();
+                                            }),
+                                        )
+                                        .child(
+                                            div()
+                                                .text_color(rgb(NORD6))
+                                                .text_size(px(13.0))
+                                                .child("Aperçu des règles"),
+                                        ),
+                                )
+                            })
+                            .when(!self.loading && !self.videos_exhausted, |this| {
+                                this.child(
+                                    // Parcourt tout l'historique de la chaîne
+                                    div()
+                                        .px_3()
+                                        .py_1()
+                                        .bg(rgb(NORD2))
+                                        .rounded_md()
+                                        .cursor_pointer()
+                                        .hover(|style| style.bg(rgb(NORD3)))
+                                        .on_mouse_down(
+                                            MouseButton::Left,
+                                            cx.listener(move |this, _event, _window, cx| {
+                                                this.load_videos(channel_index, ScanMode::Backfill, cx);
+                                            }),
+                                        )
+                                        .child(
+                                            div()
+                                                .text_color(rgb(NORD6))
+                                                .text_size(px(13.0))
+                                                .child("Tout l'historique"),
+                                        ),
+                                )
+                            }),
                     )
+                    .when(self.rules_preview && !self.loading, |this| {
+                        this.child(
+                            div()
+                                .text_color(rgb(NORD8))
+                                .text_size(px(12.0))
+                                .mb_2()
+                                .child(format!(
+                                    "{} vidéo(s) sur {} seraient téléchargées automatiquement",
+                                    matching_count,
+                                    self.videos.len()
+                                )),
+                        )
+                    })
                     .child(if self.loading {
                         div()
                             .flex()
@@ -778,25 +1415,53 @@ impl NDownloaderApp {
                             .gap_2()
                             .size_full()
                             .overflow_y_scroll()
-                            .children(self.videos.iter().map(|video| {
+                            .track_scroll(&self.video_scroll)
+                            .on_scroll_wheel(cx.listener(|this, _event: &ScrollWheelEvent, _window, cx| {
+                                // Charger la page suivante un peu avant d'atteindre le bas
+                                let offset = this.video_scroll.offset();
+                                let max_offset = this.video_scroll.max_offset();
+                                if -offset.y >= max_offset.height - px(200.0) {
+                                    this.load_more_videos(cx);
+                                }
+                            }))
+                            .children(visible_videos.into_iter().map(|video| {
                                 let video_url = video.url.clone();
-                                let channel_name = self.channels[channel_index].name.clone();
+                                let video_info = video.clone();
+                                let channel = self.channels[channel_index].clone();
                                 let status = video.status.clone();
 
                                 // Récupérer la progression si en cours de téléchargement
-                                let progress = if status == VideoStatus::Downloading {
+                                let task = if matches!(
+                                    status,
+                                    VideoStatus::Downloading
+                                        | VideoStatus::Merging
+                                        | VideoStatus::Paused
+                                ) {
                                     self.download_queue
                                         .get_tasks()
-                                        .iter()
+                                        .into_iter()
+                                        .rev()
                                         .find(|t| t.video_url == video_url)
-                                        .map(|t| t.progress)
                                 } else {
                                     None
                                 };
 
                                 let mut video_item = VideoItem::new(video.clone());
-                                if let Some(p) = progress {
-                                    video_item = video_item.with_progress(p);
+                                if let Some(task) = task {
+                                    let mut details =
+                                        vec![format!("{:.0}%", task.progress * 100.0)];
+                                    if let Some(speed) = task.speed {
+                                        details.push(download_progress::format_speed(speed));
+                                    }
+                                    if let Some(eta) = task.eta {
+                                        details.push(format!(
+                                            "ETA {}",
+                                            download_progress::format_eta(eta)
+                                        ));
+                                    }
+                                    video_item = video_item
+                                        .with_progress(task.progress)
+                                        .with_details(details.join(" · "));
                                 }
 
                                 div()
@@ -813,20 +1478,108 @@ impl NDownloaderApp {
                                                 MouseButton::Left,
                                                 cx.listener(move |this, _event, _window, cx| {
                                                     this.start_download(
-                                                        video_url.clone(),
-                                                        channel_name.clone(),
+                                                        &video_info,
+                                                        channel.clone(),
                                                         cx,
                                                     );
                                                 }),
                                             )
                                     })
                                     .child(video_item)
+                                    .when(
+                                        matches!(status, VideoStatus::Downloading | VideoStatus::Paused),
+                                        |this| {
+                                            this.child(self.render_download_controls(
+                                                video.url.clone(),
+                                                status.clone(),
+                                                cx,
+                                            ))
+                                        },
+                                    )
                             }))
+                            .when(!self.videos_exhausted, |this| {
+                                this.child(
+                                    div()
+                                        .flex()
+                                        .justify_center()
+                                        .p_2()
+                                        .text_color(rgb(NORD8))
+                                        .text_size(px(13.0))
+                                        .when(!self.loading_more, |this| {
+                                            this.cursor_pointer()
+                                                .hover(|style| style.text_color(rgb(NORD9)))
+                                                .on_mouse_down(
+                                                    MouseButton::Left,
+                                                    cx.listener(|this, _event, _window, cx| {
+                                                        this.load_more_videos(cx);
+                                                    }),
+                                                )
+                                        })
+                                        .child(if self.loading_more {
+                                            "Chargement de vidéos plus anciennes..."
+                                        } else {
+                                            "Charger plus de vidéos"
+                                        }),
+                                )
+                            })
                             .into_any_element()
                     }),
             )
     }
 
+    fn render_download_controls(
+        &self,
+        video_url: String,
+        status: VideoStatus,
+        cx: &Context<Self>,
+    ) -> Div {
+        let control_button = |label: &'static str, color: u32| {
+            div()
+                .px_2()
+                .py_1()
+                .bg(rgb(color))
+                .rounded_sm()
+                .cursor_pointer()
+                .hover(|style| style.bg(rgb(NORD3)))
+                .child(
+                    div()
+                        .text_color(rgb(NORD6))
+                        .text_size(px(12.0))
+                        .font_weight(FontWeight::BOLD)
+                        .child(label),
+                )
+        };
+
+        let toggle_url = video_url.clone();
+        let toggle = if status == VideoStatus::Paused {
+            control_button("Reprendre", NORD10).on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, _event, _window, cx| {
+                    this.resume_video_download(&toggle_url);
+                    cx.stop_propagation();
+                }),
+            )
+        } else {
+            control_button("Pause", NORD10).on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, _event, _window, cx| {
+                    this.pause_video_download(&toggle_url);
+                    cx.stop_propagation();
+                }),
+            )
+        };
+
+        div().flex().gap_2().child(toggle).child(
+            control_button("Annuler", NORD11).on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, _event, _window, cx| {
+                    this.cancel_video_download(&video_url);
+                    cx.stop_propagation();
+                }),
+            ),
+        )
+    }
+
     fn render_download_overlay(
         &mut self,
         main_content: AnyElement,
@@ -882,7 +1635,7 @@ impl NDownloaderApp {
                                     .gap_2()
                                     .child(
                                         div().text_color(rgb(NORD4)).text_size(px(13.0)).child(
-                                            "Entrez le nom du fichier (sans extension .mp4) :",
+                                            "Entrez le nom du fichier (sans extension) :",
                                         ),
                                     )
                                     .child(
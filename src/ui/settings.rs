@@ -0,0 +1,421 @@
+use super::*;
+use crate::config::{Config, StorageRole, StorageRoot, StorageStrategy};
+use std::path::PathBuf;
+
+/// Formulaire de l'écran de réglages
+pub(super) struct SettingsForm {
+    storage: Vec<StorageRow>,
+    strategy: StorageStrategy,
+    reserve_input: Entity<TextInputView>,
+    new_storage_input: Entity<TextInputView>,
+    data_dir_input: Entity<TextInputView>,
+    cache_dir_input: Entity<TextInputView>,
+    max_concurrent_input: Entity<TextInputView>,
+    scan_cache_ttl_input: Entity<TextInputView>,
+    error: Option<String>,
+}
+
+struct StorageRow {
+    input: Entity<TextInputView>,
+    role: StorageRole,
+}
+
+fn role_label(role: StorageRole) -> &'static str {
+    match role {
+        StorageRole::Primary => "Principal",
+        StorageRole::Overflow => "Débordement",
+        StorageRole::Archive => "Archive",
+    }
+}
+
+fn role_color(role: StorageRole) -> u32 {
+    match role {
+        StorageRole::Primary => NORD14,
+        StorageRole::Overflow => NORD13,
+        StorageRole::Archive => NORD9,
+    }
+}
+
+fn next_role(role: StorageRole) -> StorageRole {
+    match role {
+        StorageRole::Primary => StorageRole::Overflow,
+        StorageRole::Overflow => StorageRole::Archive,
+        StorageRole::Archive => StorageRole::Primary,
+    }
+}
+
+fn strategy_label(strategy: StorageStrategy) -> &'static str {
+    match strategy {
+        StorageStrategy::MostFreeSpace => "Le plus d'espace libre",
+        StorageStrategy::FillFirst => "Remplir dans l'ordre",
+        StorageStrategy::RoundRobin => "À tour de rôle",
+        StorageStrategy::SameDiskAsChannel => "Même disque que la chaîne",
+    }
+}
+
+fn next_strategy(strategy: StorageStrategy) -> StorageStrategy {
+    match strategy {
+        StorageStrategy::MostFreeSpace => StorageStrategy::FillFirst,
+        StorageStrategy::FillFirst => StorageStrategy::RoundRobin,
+        StorageStrategy::RoundRobin => StorageStrategy::SameDiskAsChannel,
+        StorageStrategy::SameDiskAsChannel => StorageStrategy::MostFreeSpace,
+    }
+}
+
+pub(super) fn prefilled_input(cx: &mut App, value: String) -> Entity<TextInputView> {
+    cx.new(|cx| TextInputView::new(cx).with_value(value))
+}
+
+impl SettingsForm {
+    fn new(config: &Config, cx: &mut App) -> Self {
+        let storage = config
+            .storage
+            .iter()
+            .map(|root| StorageRow {
+                input: prefilled_input(cx, root.path.display().to_string()),
+                role: root.role,
+            })
+            .collect();
+
+        Self {
+            storage,
+            strategy: config.defaults.storage_strategy,
+            reserve_input: prefilled_input(cx, config.defaults.storage_reserve_gib.to_string()),
+            new_storage_input: cx.new(|cx| {
+                TextInputView::new(cx).placeholder("Nouvel emplacement (ex: /mnt/videos)...")
+            }),
+            data_dir_input: prefilled_input(cx, config.data_dir.display().to_string()),
+            cache_dir_input: prefilled_input(cx, config.cache_dir.display().to_string()),
+            max_concurrent_input: prefilled_input(
+                cx,
+                config.defaults.max_concurrent_downloads.to_string(),
+            ),
+            scan_cache_ttl_input: prefilled_input(
+                cx,
+                config.defaults.scan_cache_ttl_secs.to_string(),
+            ),
+            error: None,
+        }
+    }
+
+    /// Construit la configuration saisie (sans la valider)
+    fn to_config(&self, current: &Config, cx: &App) -> Result<Config, String> {
+        let read = |input: &Entity<TextInputView>| input.read(cx).value().trim().to_string();
+        let parse_number = |input: &Entity<TextInputView>, field: &str| {
+            read(input)
+                .parse::<u64>()
+                .map_err(|_| format!("{field} doit être un nombre entier"))
+        };
+
+        let mut config = current.clone();
+        config.data_dir = PathBuf::from(read(&self.data_dir_input));
+        config.cache_dir = PathBuf::from(read(&self.cache_dir_input));
+        config.defaults.max_concurrent_downloads =
+            parse_number(&self.max_concurrent_input, "Téléchargements simultanés")? as usize;
+        config.defaults.scan_cache_ttl_secs =
+            parse_number(&self.scan_cache_ttl_input, "Durée du cache")?;
+        config.defaults.storage_strategy = self.strategy;
+        config.defaults.storage_reserve_gib =
+            parse_number(&self.reserve_input, "Réserve par disque")?;
+        config.storage = self
+            .storage
+            .iter()
+            .map(|row| StorageRoot {
+                path: PathBuf::from(read(&row.input)),
+                role: row.role,
+            })
+            .filter(|root| !root.path.as_os_str().is_empty())
+            .collect();
+        Ok(config)
+    }
+}
+
+impl NDownloaderApp {
+    pub(super) fn open_settings(&mut self, cx: &mut Context<Self>) {
+        self.settings = Some(SettingsForm::new(&self.config, cx));
+        cx.notify();
+    }
+
+    fn close_settings(&mut self, cx: &mut Context<Self>) {
+        self.settings = None;
+        cx.notify();
+    }
+
+    fn add_storage_row(&mut self, cx: &mut Context<Self>) {
+        let Some(form) = &mut self.settings else {
+            return;
+        };
+        let path = form.new_storage_input.read(cx).value().trim().to_string();
+        if path.is_empty() {
+            return;
+        }
+
+        form.storage.push(StorageRow {
+            input: prefilled_input(cx, path),
+            role: StorageRole::Primary,
+        });
+        form.new_storage_input
+            .update(cx, |input, _cx| input.clear());
+        cx.notify();
+    }
+
+    fn save_settings(&mut self, cx: &mut Context<Self>) {
+        let Some(form) = &mut self.settings else {
+            return;
+        };
+
+        let result = form
+            .to_config(&self.config, cx)
+            .and_then(|config| config.save().map(|_| config).map_err(|e| e.to_string()));
+
+        match result {
+            Ok(config) => {
+                // Les emplacements s'appliquent tout de suite, le reste au prochain démarrage
+                self.scanner.set_storage(&config);
+                self.config = config;
+                self.settings = None;
+                Notification::success(
+                    "Réglages enregistrés",
+                    "Les dossiers de données et de cache et le nombre de téléchargements \
+                     simultanés seront appliqués au prochain démarrage",
+                );
+            }
+            Err(error) => {
+                tracing::warn!("Invalid settings: {}", error);
+                form.error = Some(error);
+            }
+        }
+        cx.notify();
+    }
+
+    pub(super) fn render_settings(&mut self, cx: &mut Context<Self>) -> AnyElement {
+        let Some(form) = &self.settings else {
+            return div().into_any_element();
+        };
+
+        let section_title = |title: &'static str| {
+            div()
+                .text_color(rgb(NORD6))
+                .text_size(px(16.0))
+                .font_weight(FontWeight::SEMIBOLD)
+                .child(title)
+        };
+        let input_box = |input: Entity<TextInputView>| {
+            div()
+                .flex_1()
+                .h_10()
+                .px_3()
+                .bg(rgb(NORD2))
+                .border_1()
+                .border_color(rgb(NORD3))
+                .rounded_md()
+                .child(input)
+        };
+        let labeled_input = |label: &'static str, input: Entity<TextInputView>| {
+            div()
+                .flex()
+                .items_center()
+                .gap_3()
+                .child(
+                    div()
+                        .w(px(220.0))
+                        .text_color(rgb(NORD4))
+                        .text_size(px(13.0))
+                        .child(label),
+                )
+                .child(input_box(input))
+        };
+        let button = |label: &'static str, color: u32| {
+            div()
+                .px_4()
+                .py_2()
+                .bg(rgb(color))
+                .rounded_md()
+                .cursor_pointer()
+                .hover(|style| style.bg(rgb(NORD3)))
+                .child(
+                    div()
+                        .text_color(rgb(NORD6))
+                        .text_size(px(14.0))
+                        .font_weight(FontWeight::SEMIBOLD)
+                        .child(label),
+                )
+        };
+
+        let storage_rows = form.storage.iter().enumerate().map(|(index, row)| {
+            div()
+                .flex()
+                .items_center()
+                .gap_2()
+                .child(input_box(row.input.clone()))
+                .child(
+                    div()
+                        .w(px(110.0))
+                        .px_2()
+                        .py_1()
+                        .bg(rgb(role_color(row.role)))
+                        .rounded_sm()
+                        .cursor_pointer()
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(move |this, _event, _window, cx| {
+                                if let Some(row) = this
+                                    .settings
+                                    .as_mut()
+                                    .and_then(|form| form.storage.get_mut(index))
+                                {
+                                    row.role = next_role(row.role);
+                                    cx.notify();
+                                }
+                            }),
+                        )
+                        .child(
+                            div()
+                                .text_color(rgb(NORD0))
+                                .text_size(px(12.0))
+                                .font_weight(FontWeight::BOLD)
+                                .child(role_label(row.role)),
+                        ),
+                )
+                .child(button("Retirer", NORD11).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _event, _window, cx| {
+                        if let Some(form) = &mut this.settings {
+                            if index < form.storage.len() {
+                                form.storage.remove(index);
+                                cx.notify();
+                            }
+                        }
+                    }),
+                ))
+        });
+
+        div()
+            .id("settings")
+            .on_action(cx.listener(Self::go_back))
+            .on_action(cx.listener(Self::handle_quit))
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(rgb(NORD0))
+            .gap_4()
+            .p_4()
+            .overflow_y_scroll()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_4()
+                    .child(button("← Retour", NORD1).on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| this.close_settings(cx)),
+                    ))
+                    .child(
+                        div()
+                            .text_color(rgb(NORD6))
+                            .text_size(px(20.0))
+                            .font_weight(FontWeight::BOLD)
+                            .child("Réglages"),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_3()
+                    .p_4()
+                    .bg(rgb(NORD1))
+                    .rounded_md()
+                    .child(section_title("Emplacements de stockage"))
+                    .child(div().text_color(rgb(NORD4)).text_size(px(13.0)).child(
+                        "Cliquez sur le rôle pour le changer : principal, débordement \
+                         (si aucun principal n'est disponible) ou archive (lecture seule)",
+                    ))
+                    .children(storage_rows)
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap_2()
+                            .child(input_box(form.new_storage_input.clone()))
+                            .child(button("Ajouter", NORD8).on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event, _window, cx| this.add_storage_row(cx)),
+                            )),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap_3()
+                            .child(
+                                div()
+                                    .w(px(220.0))
+                                    .text_color(rgb(NORD4))
+                                    .text_size(px(13.0))
+                                    .child("Stratégie de sélection"),
+                            )
+                            .child(button(strategy_label(form.strategy), NORD10).on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event, _window, cx| {
+                                    if let Some(form) = &mut this.settings {
+                                        form.strategy = next_strategy(form.strategy);
+                                        cx.notify();
+                                    }
+                                }),
+                            )),
+                    )
+                    .child(labeled_input(
+                        "Réserve par disque (Gio)",
+                        form.reserve_input.clone(),
+                    )),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_3()
+                    .p_4()
+                    .bg(rgb(NORD1))
+                    .rounded_md()
+                    .child(section_title("Dossiers et valeurs par défaut"))
+                    .child(labeled_input(
+                        "Dossier de données",
+                        form.data_dir_input.clone(),
+                    ))
+                    .child(labeled_input(
+                        "Dossier de cache",
+                        form.cache_dir_input.clone(),
+                    ))
+                    .child(labeled_input(
+                        "Téléchargements simultanés",
+                        form.max_concurrent_input.clone(),
+                    ))
+                    .child(labeled_input(
+                        "Durée du cache des scans (s)",
+                        form.scan_cache_ttl_input.clone(),
+                    )),
+            )
+            .when_some(form.error.clone(), |this, error| {
+                this.child(
+                    div()
+                        .p_3()
+                        .bg(rgb(NORD11))
+                        .rounded_md()
+                        .text_color(rgb(NORD6))
+                        .text_size(px(13.0))
+                        .child(error),
+                )
+            })
+            .child(
+                div()
+                    .flex()
+                    .justify_end()
+                    .child(button("Enregistrer", NORD10).on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| this.save_settings(cx)),
+                    )),
+            )
+            .into_any_element()
+    }
+}
@@ -58,6 +58,11 @@ impl TextInputView {
         self
     }
 
+    pub fn with_value(mut self, text: impl Into<SharedString>) -> Self {
+        self.input.value = text.into();
+        self
+    }
+
     pub fn on_enter<F>(mut self, callback: F) -> Self
     where
         F: Fn(&str) + 'static,